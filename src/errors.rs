@@ -1,3 +1,5 @@
+use crate::extract::{Issue, Severity};
+use crate::shared::lang::{self, tr, Lang};
 use std::fmt;
 use std::path::PathBuf;
 
@@ -39,8 +41,12 @@ pub enum Error<'a> {
         sh_name: String, // нельзя ссылкой - имя листа с учетом регистра определяется внутри функции, где возможна ошибка
     },
 
+    // Два варианта ниже не возвращаются как ошибка самостоятельно, а служат текстом замечаний
+    // в отчете о проверке листа (ValidationReport), поэтому не содержат пути к файлу
     SheetNotContainAllNecessaryData {
-        file_path: PathBuf,
+        missing_tags: Vec<&'static str>,
+        misplaced_tags: Vec<(&'static str, String)>, // тег и адрес ячейки, где он найден вне установленного порядка
+        expected_order: Vec<&'static str>,
     },
 
     SheetMisalignment {
@@ -49,19 +55,46 @@ pub enum Error<'a> {
         fst_tag_index_on_sheet: String,
         snd_tag_str: &'static str,
         snd_tag_index_on_sheet: String,
+    },
+    SheetValidation {
         file_path: PathBuf,
+        issues: Vec<Issue>,
     },
 
-    XlsxwriterWorkbookCreation {
-        wb_name: &'a str,
-        err: xlsxwriter::XlsxError,
-    },
     XlsxwriterSheetCreation,
-    XlsxwriterCellWrite(xlsxwriter::XlsxError),
-    XlsxwriterFormatting(xlsxwriter::XlsxError),
+    XlsxwriterCellWrite(rust_xlsxwriter::XlsxError),
+    XlsxwriterFormatting(rust_xlsxwriter::XlsxError),
     XlsxwriterWorkbookClose {
-        wb_name: &'a str,
-        err: xlsxwriter::XlsxError,
+        wb_name: String,
+        err: rust_xlsxwriter::XlsxError,
+    },
+
+    ActStoreIo {
+        file_path: PathBuf, // временный файл с собранными актами
+        err: std::io::Error,
+    },
+    ReportFileWrite {
+        file_path: PathBuf,
+        err: std::io::Error,
+    },
+    SqliteWrite {
+        file_path: PathBuf,
+        err: rusqlite::Error,
+    },
+
+    ManifestRead {
+        file_path: PathBuf,
+        err: std::io::Error,
+    },
+
+    SettingsFileRead {
+        file_path: PathBuf,
+        err: std::io::Error,
+    },
+    SettingsInvalidEntry {
+        file_path: Option<PathBuf>, // None для переменной окружения
+        line_number: usize,
+        entry: String,
     },
 }
 
@@ -72,11 +105,14 @@ impl fmt::Display for Error<'_> {
         match self {
             Self::InternalLogic { tech_descr, err } => {
                 let base_msg = format!(
-                    "Во внутренней логике программы произошла ошибка.
-{tech_descr}"
+                    "{}\n{tech_descr}",
+                    tr(
+                        "Во внутренней логике программы произошла ошибка.",
+                        "An error occurred in the internal logic of the program."
+                    )
                 );
                 let footer_msg = match err {
-                    Some(err) => format!("Подробности об ошибке:\n{}", err),
+                    Some(err) => format!("{}\n{}", details_title(), err),
                     None => "".to_string(),
                 };
                 let full_msg = format!("{base_msg}\n\n{footer_msg}");
@@ -84,8 +120,15 @@ impl fmt::Display for Error<'_> {
             }
 
             Self::InvalidDashInUserPath { entered_path } => {
-                let base_msg = format!("Обнаружена ошибка в пути:\n{}", entered_path.display());
-                let footer_msg ="Похоже, в пути присутствует длинное тире («— копия»).
+                let base_msg = format!(
+                    "{}\n{}",
+                    tr(
+                        "Обнаружена ошибка в пути:",
+                        "An error was found in the path:"
+                    ),
+                    entered_path.display()
+                );
+                let footer_msg = tr("Похоже, в пути присутствует длинное тире («— копия»).
 Терминал windows (окно которое вы наблюдаете) иногда заменяет его на короткое, что приводит к ошибке.
 Пожалуйста, переименуйте файл или папку, удалив или заменив длинное тире. После повторите попытку.
 
@@ -93,29 +136,44 @@ P.s. Не беспокойтесь о вложенных папках, в кот
 их наличие не мешает, проблема возникает на этапе когда вы вставляете путь в окно,
 важно чтобы этот путь не исказился и длинное тире автоматически не подменилось на короткое
 (попробуйте вставить в это окно любой текст с длинным тире, и вы увидите, что оно автоматически
-меняется, что создает проблему программе принять от вас неискаженный терминалом windows путь).";
+меняется, что создает проблему программе принять от вас неискаженный терминалом windows путь).",
+"It looks like the path contains a long dash («— копия»).
+The Windows terminal (the window you are looking at) sometimes replaces it with a short one, which causes the error.
+Please rename the file or folder by removing or replacing the long dash. Then try again.
+
+P.S. Do not worry about nested folders that may contain a long dash, they do not bother the program.
+The problem occurs when you paste the path into this window: the path must not be distorted,
+and the long dash must not be automatically replaced with a short one
+(try pasting any text with a long dash into this window and you will see that it changes automatically,
+which prevents the program from receiving the path undistorted by the Windows terminal).");
                 let full_msg = format!("{base_msg}\n\n{footer_msg}");
                 write!(f, "{full_msg}")
             }
 
             Self::NumericConversion { tech_descr, err } => {
                 let base_msg = tech_descr;
-                let footer_msg = format!("Подробности об ошибке:\n{}", err);
+                let footer_msg = format!("{}\n{}", details_title(), err);
                 let full_msg = format!("{base_msg}\n\n{footer_msg}");
                 write!(f, "{full_msg}")
             }
 
             Self::NumericOverflow { tech_descr } => {
-                let base_msg = "Переполнение при операции с числами.";
-                let footer_msg = format!("Подробности об ошибке:\n{}", tech_descr);
+                let base_msg = tr(
+                    "Переполнение при операции с числами.",
+                    "Numeric overflow during an arithmetic operation.",
+                );
+                let footer_msg = format!("{}\n{}", details_title(), tech_descr);
                 let full_msg = format!("{base_msg}\n\n{footer_msg}");
                 write!(f, "{full_msg}")
             }
 
             Self::CalamineFileOpen { file_path, err } => {
-                let base_msg = "Возникла проблема с открытием книги Excel для чтения.";
-                let footer_msg = format!("Подробности об ошибке:\n{err}");
-                let path_msg = format!("Файл, вызывающий ошибку:\n{}", file_path.display());
+                let base_msg = tr(
+                    "Возникла проблема с открытием книги Excel для чтения.",
+                    "There was a problem opening the Excel workbook for reading.",
+                );
+                let footer_msg = format!("{}\n{err}", details_title());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
                 let full_msg = format!("{base_msg}\n\n{footer_msg}\n\n{path_msg}");
                 write!(f, "{full_msg}")
             }
@@ -128,35 +186,57 @@ P.s. Не беспокойтесь о вложенных папках, в кот
                 let string_sh_names = format!("{:?}", sh_names);
 
                 // базовое сообщение
-                let base_msg = format!(
-                    "Встретился файл, который не содержит запрашиваемого вами листа '{sh_name_for_search}',
+                let base_msg = tr(
+                    format!(
+                        "Встретился файл, который не содержит запрашиваемого вами листа '{sh_name_for_search}',
 так как файл имеет только следующие листы:
     
 {string_sh_names}"
+                    ),
+                    format!(
+                        "A file was found that does not contain the sheet '{sh_name_for_search}' you requested,
+since the file has only the following sheets:
+
+{string_sh_names}"
+                    ),
                 );
 
                 // опциональное сообщение про кавычки как возможную причину ошибки
                 let optional_msg =
                     if sh_name_for_search.starts_with('"') && sh_name_for_search.ends_with('"') {
-                        format!(
-                            "
+                        tr(
+                            format!(
+                                "
                         
 Обратите внимание: вы ввели имя листа, заключённое в кавычки ('{sh_name_for_search}'), эти кавычки,
 могут являться причиной ошибки, так как обычно имена листов в книгах Excel не заключают в кавычки.
 Попробуйте повторить процедуру и ввести имя листа таким, каким вы его видите в самом файле Excel."
+                            ),
+                            format!(
+                                "
+
+Note: you entered a sheet name enclosed in quotes ('{sh_name_for_search}'), these quotes
+may be the cause of the error, since sheet names in Excel workbooks are usually not quoted.
+Try again and enter the sheet name exactly as you see it in the Excel file itself."
+                            ),
                         )
                     } else {
                         "".to_string()
                     };
 
                 // заключительная часть сообщения
-                let footer_msg = "Чтобы успешно выполнить процедуру сбора файлов, выполните одно из перечисленных действий:
+                let footer_msg = tr("Чтобы успешно выполнить процедуру сбора файлов, выполните одно из перечисленных действий:
 - откройте файл, вызывающий ошибку, и присвойте листу с актом имя, которое затем укажете программе;
 - если не хотите собирать этот файл, переименуйте файл, добавив к существующему имени символ '@',
   или удалите файл из папки;
-- если не хотите собирать папку, где находится файл, добавьте к существующему имени папки символ '@'.";
+- если не хотите собирать папку, где находится файл, добавьте к существующему имени папки символ '@'.",
+"To complete the collection successfully, do one of the following:
+- open the file causing the error and give the sheet with the act the name you will then enter in the program;
+- if you do not want to collect this file, rename it by adding the '@' symbol to its name,
+  or remove the file from the folder;
+- if you do not want to collect the folder containing the file, add the '@' symbol to the folder name.");
 
-                let path_msg = format!("Файл, вызывающий ошибку:\n{}", file_path.display());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
 
                 // объединение всех частей в одно сообщение
                 let full_msg = format!("{base_msg}{optional_msg}\n\n{footer_msg}\n\n{path_msg}");
@@ -168,42 +248,96 @@ P.s. Не беспокойтесь о вложенных папках, в кот
                 sh_name,
                 err,
             } => {
-                let base_msg = format!("Возникла проблема с чтением листа '{sh_name}'.");
-                let footer_msg = format!("Подробности об ошибке:\n{err}");
-                let path_msg = format!("Файл, вызывающий ошибку:\n{}", file_path.display());
+                let base_msg = tr(
+                    format!("Возникла проблема с чтением листа '{sh_name}'."),
+                    format!("There was a problem reading the sheet '{sh_name}'."),
+                );
+                let footer_msg = format!("{}\n{err}", details_title());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
                 let full_msg = format!("{base_msg}\n\n{footer_msg}\n\n{path_msg}");
                 write!(f, "{full_msg}")
             }
 
             Self::EmptySheetRange { file_path, sh_name } => {
-                let base_msg = format!("Лист '{sh_name}' не содержит данных (пуст).");
-                let path_msg = format!("Файл, вызывающий ошибку:\n{}", file_path.display());
+                let base_msg = tr(
+                    format!("Лист '{sh_name}' не содержит данных (пуст)."),
+                    format!("The sheet '{sh_name}' contains no data (empty)."),
+                );
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
                 let full_msg = format!("{base_msg}\n\n{path_msg}");
                 write!(f, "{full_msg}")
             }
 
-            Self::SheetNotContainAllNecessaryData { file_path } => {
-                let base_msg = "Акт не содержит полных данных.
-От собираемого файла требуется следующий набор ключевых слов:
-    'Стройка',
-    'Объект',
-    'Договор подряда',
-    'Доп. соглашение',
-    'Номер документа',
-    'О ПРИЕМКЕ ВЫПОЛЕНЕННЫХ РАБОТ',
-    'Наименование работ и затрат',
-    'Стоимость в ценах 2001',
-    'Стоимость в текущих ценах',
-    'Стоимость материальных ресурсов (всего)'.
-    
-Если чего-то из перечисленного в акте не обнаружено, такой акт не может быть собран.
-Проверьте документ на наличие перечисленных ключевых слов. Если ошибка происходит при наличии всех
-ключевых слов - проверьте строковый порядок: вхождение слов по строкам должно быть в порядке
-перечисленом выше (т.е. в файле строка 'Стройка' должна быть выше строки с 'Объект', а 'Объект',
-в свою очередь, расположен выше (или левее) строки с текстом 'Договор подряда' и так далее).";
-                let path_msg = format!("Файл, вызывающий ошибку:\n{}", file_path.display());
-                let full_msg = format!("{base_msg}\n\n{path_msg}");
-                write!(f, "{full_msg}")
+            Self::SheetNotContainAllNecessaryData {
+                missing_tags,
+                misplaced_tags,
+                expected_order,
+            } => {
+                let mut msg_parts = vec![tr(
+                    "Акт не содержит полных данных.",
+                    "The act does not contain complete data.",
+                )
+                .to_string()];
+
+                if !missing_tags.is_empty() {
+                    let tags_list = missing_tags
+                        .iter()
+                        .map(|tag| format!("    '{tag}'"))
+                        .collect::<Vec<_>>()
+                        .join(",\n");
+                    msg_parts.push(format!(
+                        "{}\n{tags_list}.",
+                        tr(
+                            "В акте не найдены ключевые слова:",
+                            "The following keywords were not found in the act:"
+                        )
+                    ));
+                }
+
+                if !misplaced_tags.is_empty() {
+                    let tags_list = misplaced_tags
+                        .iter()
+                        .map(|(tag, address)| {
+                            tr(
+                                format!("    '{tag}' в ячейке {address}"),
+                                format!("    '{tag}' in cell {address}"),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(",\n");
+                    msg_parts.push(format!(
+                        "{}\n{tags_list}.",
+                        tr(
+                            "Ключевые слова найдены, но нарушают установленный порядок:",
+                            "The following keywords were found, but break the required order:"
+                        )
+                    ));
+                }
+
+                let order_list = expected_order
+                    .iter()
+                    .map(|tag| format!("    '{tag}'"))
+                    .collect::<Vec<_>>()
+                    .join(",\n");
+                msg_parts.push(tr(
+                    format!(
+                        "Вхождение ключевых слов по строкам должно быть в следующем порядке:
+{order_list}.
+(т.е. в файле строка 'Стройка' должна быть выше строки с 'Объект', а 'Объект', в свою очередь,
+расположен выше (или левее) строки с текстом 'Договор подряда' и так далее).
+Если чего-то из перечисленного в акте не обнаружено, такой акт не может быть собран."
+                    ),
+                    format!(
+                        "The keywords must appear in the rows in the following order:
+{order_list}.
+(i.e. in the file the row 'Стройка' must be above the row with 'Объект', and 'Объект', in turn,
+is located above (or to the left of) the row with the text 'Договор подряда' and so on).
+If any of the above is not found in the act, such an act cannot be collected."
+                    ),
+                ));
+
+                let base_msg = msg_parts.join("\n\n");
+                write!(f, "{base_msg}")
             }
 
             Self::SheetMisalignment {
@@ -212,69 +346,213 @@ P.s. Не беспокойтесь о вложенных папках, в кот
                 fst_tag_index_on_sheet,
                 snd_tag_str,
                 snd_tag_index_on_sheet,
-                file_path,
             } => {
-                let alignment_type = if *is_row_algmnt_check {
-                    "строке"
-                } else {
-                    "колонке"
+                let alignment_type = match (*is_row_algmnt_check, current_is_ru()) {
+                    (true, true) => "строке",
+                    (false, true) => "колонке",
+                    (true, false) => "row",
+                    (false, false) => "column",
                 };
 
-                let base_msg = format!(
+                let base_msg = tr(format!(
 "Ожидалось, что ключевые слова '{fst_tag_str}' и '{snd_tag_str}'
 будут оба находиться в одной и той же {alignment_type}.
 Однако, '{fst_tag_str}' найдено в {alignment_type} '{fst_tag_index_on_sheet}', а '{snd_tag_str}' — в {alignment_type} '{snd_tag_index_on_sheet}'.
 
-Для исправления ошибки разместите оба ключевых слова в одной {alignment_type}.");
+Для исправления ошибки разместите оба ключевых слова в одной {alignment_type}."),
+format!(
+"The keywords '{fst_tag_str}' and '{snd_tag_str}' were expected
+to be both located in the same {alignment_type}.
+However, '{fst_tag_str}' was found in {alignment_type} '{fst_tag_index_on_sheet}', and '{snd_tag_str}' in {alignment_type} '{snd_tag_index_on_sheet}'.
 
-                let path_msg = format!("Файл, вызывающий ошибку:\n{}", file_path.display());
-                let full_msg = format!("{base_msg}\n\n{path_msg}");
-                write!(f, "{full_msg}")
+To fix the error, place both keywords in the same {alignment_type}."));
+
+                write!(f, "{base_msg}")
             }
 
-            Self::XlsxwriterWorkbookCreation { wb_name, err } => {
-                let base_msg = format!(
-                    "Не удалась попытка создания файла Excel с именем '{wb_name}'."
+            Self::SheetValidation { file_path, issues } => {
+                let error_count = issues
+                    .iter()
+                    .filter(|issue| issue.severity == Severity::Error)
+                    .count();
+                let base_msg = tr(
+                    format!("Лист акта не прошел проверку, ошибок: {error_count}. Исправьте их все, чтобы собрать файл."),
+                    format!("The act sheet failed validation, errors: {error_count}. Fix all of them to collect the file."),
                 );
-                let footer_msg = format!("Подробности об ошибке:\n{err}");
-
-                let full_msg = format!("{base_msg}\n\n{footer_msg}");
+                let issues_msg = issues
+                    .iter()
+                    .enumerate()
+                    .map(|(index, issue)| {
+                        format!(
+                            "{}. [{}] {}",
+                            index + 1,
+                            issue.severity.as_str(),
+                            issue.message
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
+                let full_msg = format!("{base_msg}\n\n{issues_msg}\n\n{path_msg}");
                 write!(f, "{full_msg}")
             }
 
             Self::XlsxwriterSheetCreation => {
-                let msg = "Не удалась попытка создание листа результата внутри отчетного файла Excel, речь о листе Excel на котором
-должен был быть записан результат работы программы.";
+                let msg = tr("Не удалась попытка создание листа результата внутри отчетного файла Excel, речь о листе Excel на котором
+должен был быть записан результат работы программы.",
+"Failed to create the result sheet inside the Excel report file, i.e. the Excel sheet on which
+the result of the program was to be written.");
                 write!(f, "{msg}")
             }
 
             Self::XlsxwriterCellWrite(err) => {
-                let base_msg =
-                    "Не удалась попытка записи данных в ячейку отчетного файла Excel.";
+                let base_msg = tr(
+                    "Не удалась попытка записи данных в ячейку отчетного файла Excel.",
+                    "Failed to write data to a cell of the Excel report file.",
+                );
 
-                let footer_msg = format!("Подробности об ошибке:\n{err}");
+                let footer_msg = format!("{}\n{err}", details_title());
                 let full_msg = format!("{base_msg}\n\n{footer_msg}");
                 write!(f, "{full_msg}")
             }
 
             Self::XlsxwriterFormatting(err) => {
-                let base_msg =
-                    "Не удалась попытка форматирования отчетного файла Excel";
+                let base_msg = tr(
+                    "Не удалась попытка форматирования отчетного файла Excel",
+                    "Failed to format the Excel report file",
+                );
 
-                let footer_msg = format!("Подробности об ошибке:\n{err}");
+                let footer_msg = format!("{}\n{err}", details_title());
                 let full_msg = format!("{base_msg}\n\n{footer_msg}");
                 write!(f, "{full_msg}")
             }
 
-            Self::XlsxwriterWorkbookClose { wb_name, .. } => {
-                let msg = format!(
-                    "Не удалось сохранение на диск файла Excel с именем '{wb_name}', который содержит
+            Self::XlsxwriterWorkbookClose { wb_name, err } => {
+                let base_msg = tr(
+                    format!(
+                        "Не удалось сохранение на диск файла Excel с именем '{wb_name}', который содержит
 результат работы программы.
                     
 Вероятная причина ошибки: не закрыт файл Excel с результатами прошлого сбора."
+                    ),
+                    format!(
+                        "Failed to save to disk the Excel file named '{wb_name}', which contains
+the result of the program.
+
+Probable cause of the error: the Excel file with the results of the previous collection is not closed."
+                    ),
                 );
-                write!(f, "{msg}")
+                let footer_msg = format!("{}\n{err}", details_title());
+                let full_msg = format!("{base_msg}\n\n{footer_msg}");
+                write!(f, "{full_msg}")
+            }
+
+            Self::ActStoreIo { file_path, err } => {
+                let base_msg = tr(
+                    "Не удалось записать или прочитать временный файл с собранными актами.",
+                    "Failed to write or read the temporary file with the collected acts.",
+                );
+                let footer_msg = format!("{}\n{err}", details_title());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
+                let full_msg = format!("{base_msg}\n\n{footer_msg}\n\n{path_msg}");
+                write!(f, "{full_msg}")
+            }
+
+            Self::ReportFileWrite { file_path, err } => {
+                let base_msg = tr(
+                    "Не удалось записать файл отчета.
+
+Вероятная причина ошибки: файл открыт в другой программе.",
+                    "Failed to write the report file.
+
+Probable cause of the error: the file is open in another program.",
+                );
+                let footer_msg = format!("{}\n{err}", details_title());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
+                let full_msg = format!("{base_msg}\n\n{footer_msg}\n\n{path_msg}");
+                write!(f, "{full_msg}")
+            }
+
+            Self::SqliteWrite { file_path, err } => {
+                let base_msg = tr(
+                    "Не удалось записать базу SQLite с отчетом.
+
+Вероятная причина ошибки: база открыта в другой программе.",
+                    "Failed to write the SQLite database with the report.
+
+Probable cause of the error: the database is open in another program.",
+                );
+                let footer_msg = format!("{}\n{err}", details_title());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
+                let full_msg = format!("{base_msg}\n\n{footer_msg}\n\n{path_msg}");
+                write!(f, "{full_msg}")
+            }
+
+            Self::ManifestRead { file_path, err } => {
+                let base_msg = tr(
+                    "Не удалось прочитать список файлов к сбору.
+
+Список должен быть текстовым файлом в кодировке UTF-8.",
+                    "Failed to read the list of files to collect.
+
+The list must be a text file in UTF-8 encoding.",
+                );
+                let footer_msg = format!("{}\n{err}", details_title());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
+                let full_msg = format!("{base_msg}\n\n{footer_msg}\n\n{path_msg}");
+                write!(f, "{full_msg}")
+            }
+
+            Self::SettingsFileRead { file_path, err } => {
+                let base_msg = tr(
+                    "Не удалось прочитать файл настроек программы.",
+                    "Failed to read the program settings file.",
+                );
+                let footer_msg = format!("{}\n{err}", details_title());
+                let path_msg = format!("{}\n{}", file_path_title(), file_path.display());
+                let full_msg = format!("{base_msg}\n\n{footer_msg}\n\n{path_msg}");
+                write!(f, "{full_msg}")
+            }
+
+            Self::SettingsInvalidEntry {
+                file_path,
+                line_number,
+                entry,
+            } => {
+                let base_msg = tr(
+                    format!("Недопустимая настройка программы: '{entry}'."),
+                    format!("Invalid program setting: '{entry}'."),
+                );
+                let origin_msg = match file_path {
+                    Some(path) => tr(
+                        format!("Файл настроек:\n{}\nстрока {line_number}.", path.display()),
+                        format!("Settings file:\n{}\nline {line_number}.", path.display()),
+                    ),
+                    None => tr(
+                        "Источник: переменная окружения.",
+                        "Source: environment variable.",
+                    )
+                    .to_string(),
+                };
+                let footer_msg = tr(
+                    "Будут использованы настройки по умолчанию.",
+                    "Default settings will be used.",
+                );
+                let full_msg = format!("{base_msg}\n{origin_msg}\n\n{footer_msg}");
+                write!(f, "{full_msg}")
             }
         }
     }
 }
+
+fn details_title() -> &'static str {
+    tr("Подробности об ошибке:", "Error details:")
+}
+
+fn file_path_title() -> &'static str {
+    tr("Файл, вызывающий ошибку:", "File causing the error:")
+}
+
+fn current_is_ru() -> bool {
+    lang::current() == Lang::Ru
+}
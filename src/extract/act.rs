@@ -1,8 +1,11 @@
+use super::field_type::{self, FieldType};
 use super::sheet::Sheet;
-use super::tags::{TagAddressMap, TagID};
+use super::tags::{TagAddressMap, TagID, TAG_INFO_ARRAY};
+use super::validation::{Severity, ValidationReport};
 use crate::errors::Error;
+use crate::shared::lang::tr;
 use crate::shared::types::XlDataType;
-use calamine::DataType;
+use calamine::{Data, DataType};
 
 #[derive(Debug, Clone)]
 pub struct CellCoords {
@@ -14,39 +17,69 @@ pub struct CellCoords {
 pub struct DesiredCell {
     pub name: &'static str,
     pub cell_coords: Option<CellCoords>,
+    pub field_type: FieldType,
+    pub search: Option<NeighbourhoodSearch>,
 }
 
-// Some смещение безопасно задавать только обязательным тегом
-// порядок внутри массива не имеет значения
+// Окно поиска значения: смещения строк и столбцов (включительно) относительно расчетного адреса.
+// Нужно для полей, которые съезжают при вставке или удалении столбца в форме.
+// Поля одной строки с одинаковым окном ищутся вместе, с общим смещением (см. locate_header_cells)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeighbourhoodSearch {
+    pub rows: (i8, i8),
+    pub cols: (i8, i8),
+}
+
+// Адрес ячейки шапки: рассчитанный по смещениям от тегов и тот, где значение найдено фактически
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct HeaderCellAddress {
+    pub calculated: (usize, usize),
+    pub found: (usize, usize),
+}
+
+const SEARCH_IN_ROW: Option<NeighbourhoodSearch> = Some(NeighbourhoodSearch {
+    rows: (0, 0),
+    cols: (-2, 2),
+});
+const SEARCH_NEAR_IN_ROW: Option<NeighbourhoodSearch> = Some(NeighbourhoodSearch {
+    rows: (0, 0),
+    cols: (-1, 1),
+});
+
+// Some смещение безопасно задавать только обязательным тегом.
+// Поиск не заходит в ячейки тегов и подтвержденные значения полей без поиска, а ячейку, найденную
+// группой полей выше по списку, группа ниже по списку уже не займет
 #[rustfmt::skip]
-const DESIRED_CELLS_ARRAY: [DesiredCell; 19] = [
-    DesiredCell{name:"Генподрядчик",                 cell_coords: None},
-    DesiredCell{name:"Субподрядчик",                 cell_coords: None},
-    DesiredCell{name:"Исполнитель",                  cell_coords: None},
-    DesiredCell{name:"Глава",                        cell_coords: None},
-    DesiredCell{name:"Глава наименование",           cell_coords: None},
-    DesiredCell{name:"Объект",                       cell_coords: Some(CellCoords{row: (TagID::Объект, 0),                      col: (TagID::НаименованиеРаботИЗатрат, 0)})},
-    DesiredCell{name:"Договор №",                    cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 0),              col: (TagID::ДоговорПодряда, 2)})},
-    DesiredCell{name:"Договор дата",                 cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 1),              col: (TagID::ДоговорПодряда, 2)})},
-    DesiredCell{name:"Смета №",                      cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 0),              col: (TagID::Стройка, 0)})},
-    DesiredCell{name:"Смета наименование",           cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 1),              col: (TagID::Стройка, 0)})},
-    DesiredCell{name:"По смете в ц.2000г.",          cell_coords: Some(CellCoords{row: (TagID::ДопСоглашение, 0),               col: (TagID::НомерДокумента, 0)})},
-    DesiredCell{name:"Выполнение работ в ц.2000г.",  cell_coords: Some(CellCoords{row: (TagID::ДопСоглашение, 1),               col: (TagID::НомерДокумента, 0)})},
-    DesiredCell{name:"Акт №",                        cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 0)})},
-    DesiredCell{name:"Акт дата",                     cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 4)})},
-    DesiredCell{name:"Отчетный период начало",       cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 5)})},
-    DesiredCell{name:"Отчетный период окончание",    cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 6)})},
-    DesiredCell{name:"Акт вид",                      cell_coords: Some(CellCoords{row: (TagID::ОПриемкеВыполненныхРабот, -1),   col: (TagID::Стройка, 0)})},
-    DesiredCell{name:"Метод расчета",                cell_coords: Some(CellCoords{row: (TagID::НаименованиеРаботИЗатрат, -1),   col: (TagID::Стройка, 0)})},
-    DesiredCell{name:"Затраты труда, чел.-час",      cell_coords: None}, // небезопасно задать как Some, необязательные теги требуют особого подхода
+pub(super) const DESIRED_CELLS_ARRAY: [DesiredCell; 19] = [
+    DesiredCell{name:"Генподрядчик",                 cell_coords: None,                                                                                                             field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Субподрядчик",                 cell_coords: None,                                                                                                             field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Исполнитель",                  cell_coords: None,                                                                                                             field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Глава",                        cell_coords: None,                                                                                                             field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Глава наименование",           cell_coords: None,                                                                                                             field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Объект",                       cell_coords: Some(CellCoords{row: (TagID::Объект, 0),                      col: (TagID::НаименованиеРаботИЗатрат, 0)}), field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Договор №",                    cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 0),              col: (TagID::ДоговорПодряда, 2)}),           field_type: FieldType::DocNumber,   search: SEARCH_IN_ROW},
+    DesiredCell{name:"Договор дата",                 cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 1),              col: (TagID::ДоговорПодряда, 2)}),           field_type: FieldType::Date,        search: SEARCH_IN_ROW},
+    DesiredCell{name:"Смета №",                      cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 0),              col: (TagID::Стройка, 0)}),                  field_type: FieldType::DocNumber,   search: None},
+    DesiredCell{name:"Смета наименование",           cell_coords: Some(CellCoords{row: (TagID::ДоговорПодряда, 1),              col: (TagID::Стройка, 0)}),                  field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"По смете в ц.2000г.",          cell_coords: Some(CellCoords{row: (TagID::ДопСоглашение, 0),               col: (TagID::НомерДокумента, 0)}),           field_type: FieldType::Money,       search: None},
+    DesiredCell{name:"Выполнение работ в ц.2000г.",  cell_coords: Some(CellCoords{row: (TagID::ДопСоглашение, 1),               col: (TagID::НомерДокумента, 0)}),           field_type: FieldType::Money,       search: None},
+    DesiredCell{name:"Акт №",                        cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 0)}),           field_type: FieldType::DocNumber,   search: None},
+    DesiredCell{name:"Акт дата",                     cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 4)}),           field_type: FieldType::Date,        search: SEARCH_IN_ROW},
+    DesiredCell{name:"Отчетный период начало",       cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 5)}),           field_type: FieldType::Date,        search: SEARCH_IN_ROW},
+    DesiredCell{name:"Отчетный период окончание",    cell_coords: Some(CellCoords{row: (TagID::НомерДокумента, 2),              col: (TagID::НомерДокумента, 6)}),           field_type: FieldType::Date,        search: SEARCH_IN_ROW},
+    DesiredCell{name:"Акт вид",                      cell_coords: Some(CellCoords{row: (TagID::ОПриемкеВыполненныхРабот, -1),   col: (TagID::Стройка, 0)}),                  field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Метод расчета",                cell_coords: Some(CellCoords{row: (TagID::НаименованиеРаботИЗатрат, -1),   col: (TagID::Стройка, 0)}),                  field_type: FieldType::Text,        search: None},
+    DesiredCell{name:"Затраты труда, чел.-час",      cell_coords: None,                                                                                                             field_type: FieldType::Number,      search: SEARCH_NEAR_IN_ROW}, // небезопасно задать как Some, необязательные теги требуют особого подхода
 ];
 
 #[derive(Debug, Clone)]
 pub struct TotalsRow {
     pub name: String,
-    pub base_price: Vec<Option<f64>>,
-    pub curr_price: Vec<Option<f64>>,
+    pub base_price: Vec<Option<XlDataType>>,
+    pub curr_price: Vec<Option<XlDataType>>,
     pub row_number: Vec<usize>,
+    pub original_names: Vec<String>, // имена строк в том виде, как они записаны в акте (для сверки после нормализации)
+    pub is_percent: bool, // строка процентов надбавки, выделенная группировкой по проценту (normalize_totals_names)
 }
 
 #[derive(Debug, Clone)]
@@ -57,31 +90,107 @@ pub struct Act {
     pub data_of_header: Vec<Option<XlDataType>>,
     pub data_of_totals: Vec<TotalsRow>,
     pub start_row_of_totals: usize,
+    pub validation: ValidationReport, // предупреждения и сведения, с которыми акт собран
 }
 
 impl Act {
-    pub fn new(sheet: Sheet) -> Result<Act, Error<'static>> {
-        let header_addresses = Self::calculate_header_cell_addresses(&sheet.tag_address_map)?;
-        // println!("{:#?}", header_addresses);
+    pub fn new(mut sheet: Sheet) -> Result<Act, Error<'static>> {
+        let mut validation = std::mem::take(&mut sheet.validation);
+
+        // адрес, который не удалось вычислить, не прерывает разбор: ошибка копится в отчете о проверке
+        let header_addresses: Vec<Option<(usize, usize)>> = Self::locate_header_cells(&sheet)
+            .into_iter()
+            .zip(DESIRED_CELLS_ARRAY.iter())
+            .map(|(located, item)| match located {
+                Ok(Some(HeaderCellAddress { calculated, found })) => {
+                    if found != calculated {
+                        let calculated_a1 = sheet.get_a1_address(calculated).unwrap_or_default();
+                        let found_a1 = sheet.get_a1_address(found).unwrap_or_default();
+                        // форма отличается от ожидаемой: значение стоит проверить
+                        validation.push(
+                            Severity::Warning,
+                            tr(
+                                format!("Значение ячейки '{}' найдено в {found_a1} вместо расчетного адреса {calculated_a1}.", item.name),
+                                format!("The value of the cell '{}' was found in {found_a1} instead of the calculated address {calculated_a1}.", item.name),
+                            ),
+                        );
+                    }
+                    Some(found)
+                }
+                Ok(None) => None,
+                Err(err) => {
+                    validation.push(
+                        Severity::Error,
+                        tr(
+                            format!("Не удалось вычислить адрес ячейки '{}'.\n{err}", item.name),
+                            format!("Failed to calculate the address of the cell '{}'.\n{err}", item.name),
+                        ),
+                    );
+                    None
+                }
+            })
+            .collect();
+
         let data_of_header: Vec<Option<XlDataType>> = header_addresses
             .iter()
-            .map(|address| {
-                // println!("Обрабатывается адрес: {:?}", address);
-
-                match address {
-                    Some(adr) => match &sheet.data[*adr] {
-                        DataType::DateTime(x) => Some(XlDataType::Float(*x)),
-                        DataType::Float(x) => Some(XlDataType::Float(*x)),
-                        DataType::String(x) => {
-                            Some(XlDataType::String(x.trim().replace("\r\n", "")))
-                        }
-                        _ => None,
-                    },
-                    None => None,
+            .zip(DESIRED_CELLS_ARRAY.iter())
+            .map(|(address, desired_cell)| {
+                let adr = (*address)?;
+                let value = sheet.get_value(adr)?;
+                let converted = desired_cell.field_type.convert(value);
+                if let Some(XlDataType::Error(err)) = &converted {
+                    let a1_address = sheet.get_a1_address(adr).unwrap_or_default();
+                    validation.push(
+                        Severity::Warning,
+                        tr(
+                            format!("Ячейка '{}' ({a1_address}) содержит ошибку Excel {err}.", desired_cell.name),
+                            format!("The cell '{}' ({a1_address}) contains the Excel error {err}.", desired_cell.name),
+                        ),
+                    );
                 }
+                if converted.is_none() && !field_type::is_blank(value) {
+                    let a1_address = sheet.get_a1_address(adr).unwrap_or_default();
+                    let field_type = desired_cell.field_type;
+                    let raw_value = field_type.raw_value(value);
+                    let msg = match raw_value {
+                        Some(_) => tr(
+                            format!(
+                                "Значение ячейки '{}' ({a1_address}) не соответствует типу '{}' и собрано как есть: {:?}.",
+                                desired_cell.name,
+                                field_type.as_str(),
+                                value
+                            ),
+                            format!(
+                                "The value of the cell '{}' ({a1_address}) does not match the type '{}' and was collected as is: {:?}.",
+                                desired_cell.name,
+                                field_type.as_str(),
+                                value
+                            ),
+                        ),
+                        None => tr(
+                            format!(
+                                "Значение ячейки '{}' ({a1_address}) не соответствует типу '{}' и не будет собрано: {:?}.",
+                                desired_cell.name,
+                                field_type.as_str(),
+                                value
+                            ),
+                            format!(
+                                "The value of the cell '{}' ({a1_address}) does not match the type '{}' and will not be collected: {:?}.",
+                                desired_cell.name,
+                                field_type.as_str(),
+                                value
+                            ),
+                        ),
+                    };
+                    validation.push(Severity::Warning, msg);
+                    return raw_value;
+                }
+                converted
             })
             .collect();
 
+        let mut validation = validation.into_result(&sheet.path)?;
+
         let (start_row_of_totals_in_range, start_col_of_totals_in_range) =
             *sheet
                 .tag_address_map
@@ -90,6 +199,7 @@ impl Act {
         let data_of_totals = Self::get_totals(
             &sheet,
             (start_row_of_totals_in_range, start_col_of_totals_in_range),
+            &mut validation,
         )?;
 
         let start_row_of_totals = start_row_of_totals_in_range + sheet.range_start.0 + 1;
@@ -100,50 +210,164 @@ impl Act {
             data_of_header,
             data_of_totals,
             start_row_of_totals,
+            validation,
         })
     }
-    fn calculate_header_cell_addresses(
+
+    // Адреса всех ячеек шапки в порядке DESIRED_CELLS_ARRAY. Поля с окном поиска, расчетные адреса
+    // которых лежат в одной строке, ищутся группой с общим смещением: вставка столбца в форму сдвигает
+    // все значения строки разом, и смещение выбирается то, при котором значение ожидаемого вида
+    // находят больше всего полей группы (при равенстве - ближайшее к расчетному адресу)
+    pub(super) fn locate_header_cells(
+        sheet: &Sheet,
+    ) -> Vec<Result<Option<HeaderCellAddress>, Error<'static>>> {
+        let calculated_addresses: Vec<Result<Option<(usize, usize)>, Error<'static>>> =
+            DESIRED_CELLS_ARRAY
+                .iter()
+                .map(|item| Self::calculate_header_cell_address(&sheet.tag_address_map, item))
+                .collect();
+
+        // Ячейки тегов и подтвержденные значения полей без поиска не могут оказаться значением поля
+        // с поиском. Расчетные адреса соседних полей не резервируются: в сдвинутой форме они как раз
+        // и содержат чужие значения
+        let mut taken: Vec<(usize, usize)> = TAG_INFO_ARRAY
+            .iter()
+            .filter_map(|tag_info| sheet.tag_address_map.get(&tag_info.id).ok().copied())
+            .chain(
+                calculated_addresses
+                    .iter()
+                    .zip(DESIRED_CELLS_ARRAY.iter())
+                    .filter(|(_, item)| item.search.is_none())
+                    .filter_map(|(address, item)| {
+                        let address = address.as_ref().ok().copied().flatten()?;
+                        sheet
+                            .get_value(address)
+                            .is_some_and(|value| item.field_type.fits(value))
+                            .then(|| sheet.resolve_merged_address(address))
+                    }),
+            )
+            .collect();
+
+        let mut offsets: Vec<Option<(isize, isize)>> = vec![None; DESIRED_CELLS_ARRAY.len()];
+        for (index, item) in DESIRED_CELLS_ARRAY.iter().enumerate() {
+            let (Some(search), Ok(Some(calculated))) = (&item.search, &calculated_addresses[index])
+            else {
+                continue;
+            };
+            if offsets[index].is_some() {
+                continue;
+            }
+
+            let group: Vec<(usize, (usize, usize))> = DESIRED_CELLS_ARRAY
+                .iter()
+                .zip(calculated_addresses.iter())
+                .enumerate()
+                .skip(index)
+                .filter_map(|(other_index, (other, other_calculated))| {
+                    let other_calculated = other_calculated.as_ref().ok().copied().flatten()?;
+                    (other.search.as_ref() == Some(search) && other_calculated.0 == calculated.0)
+                        .then_some((other_index, other_calculated))
+                })
+                .collect();
+
+            let offset = search_group_offset(sheet, &group, search, &taken);
+            for (group_index, group_calculated) in group {
+                offsets[group_index] = Some(offset);
+                let Some(found) = shift_address(group_calculated, offset) else {
+                    continue;
+                };
+                let is_found = sheet
+                    .get_value(found)
+                    .is_some_and(|value| DESIRED_CELLS_ARRAY[group_index].field_type.fits(value));
+                if is_found {
+                    taken.push(sheet.resolve_merged_address(found));
+                }
+            }
+        }
+
+        calculated_addresses
+            .into_iter()
+            .zip(offsets)
+            .map(|(calculated, offset)| {
+                let Some(calculated) = calculated? else {
+                    return Ok(None);
+                };
+                let found = offset
+                    .and_then(|offset| shift_address(calculated, offset))
+                    .unwrap_or(calculated);
+                Ok(Some(HeaderCellAddress { calculated, found }))
+            })
+            .collect()
+    }
+
+    // Поля шапки, адрес которых не вычисляется без тега: адреса полей сравниваются на условном листе
+    // со всеми тегами и на том же листе без проверяемого тега. Теги на условном листе стоят
+    // в порядке TAG_INFO_ARRAY через строку, как в форме
+    pub(super) fn header_fields_depending_on(tag_id: TagID) -> Vec<&'static str> {
+        let tag_address_map = |skipped: Option<TagID>| {
+            let mut map = TagAddressMap::new();
+            for (index, tag_info) in TAG_INFO_ARRAY.iter().enumerate() {
+                if Some(tag_info.id) != skipped {
+                    map.insert(tag_info.id, (index * 2 + 2, index + 2));
+                }
+            }
+            map
+        };
+        let full_map = tag_address_map(None);
+        let map_without_tag = tag_address_map(Some(tag_id));
+
+        DESIRED_CELLS_ARRAY
+            .iter()
+            .filter(|item| {
+                let is_located = |map: &TagAddressMap| {
+                    matches!(Self::calculate_header_cell_address(map, item), Ok(Some(_)))
+                };
+                is_located(&full_map) && !is_located(&map_without_tag)
+            })
+            .map(|item| item.name)
+            .collect()
+    }
+
+    pub(super) fn calculate_header_cell_address(
         tag_address_map: &TagAddressMap,
-    ) -> Result<Vec<Option<(usize, usize)>>, Error<'static>> {
-        let stroika_adr = tag_address_map.get(&TagID::Стройка)?;
-        let object_adr = tag_address_map.get(&TagID::Объект)?;
-        let naimenov_rabot_i_zatr_adr = tag_address_map.get(&TagID::НаименованиеРаботИЗатрат)?;
-        // Предполагается что строка с Главой должна четко находиться между "Стройкой" и "Объектом"
-        let is_valid_glava = stroika_adr.0 + 2 == object_adr.0;
-        let mut vec: Vec<Option<(usize, usize)>> = Vec::new();
-
-        for item in DESIRED_CELLS_ARRAY {
-            let temp_cells_address: Option<(usize, usize)> = match item {
-                DesiredCell {
-                    name: _,
-                    cell_coords: Some(cell_coords_struct),
-                } => Some(calculate_cell_adr_by_coords(tag_address_map, cell_coords_struct)?),
-                // рукав обработывает ячейки, поиск которых основан на необязательных тегах (индивидуальная логика)
-                DesiredCell { name, .. } => match name {
+        item: &DesiredCell,
+    ) -> Result<Option<(usize, usize)>, Error<'static>> {
+        let address = match item {
+            DesiredCell {
+                cell_coords: Some(cell_coords_struct),
+                ..
+            } => Some(calculate_cell_adr_by_coords(
+                tag_address_map,
+                cell_coords_struct.clone(),
+            )?),
+            // рукав обработывает ячейки, поиск которых основан на необязательных тегах (индивидуальная логика)
+            DesiredCell { name, .. } => {
+                let naimenov_rabot_i_zatr_adr =
+                    tag_address_map.get(&TagID::НаименованиеРаботИЗатрат)?;
+
+                match *name {
                     "Генподрядчик" => tag_address_map
-                    .get(&TagID::Генподрядчик)
-                    .ok()
-                    .map(|(row_adr, _)| (*row_adr, naimenov_rabot_i_zatr_adr.1)),
+                        .get(&TagID::Генподрядчик)
+                        .ok()
+                        .map(|(row_adr, _)| (*row_adr, naimenov_rabot_i_zatr_adr.1)),
                     "Субподрядчик" => tag_address_map
-                    .get(&TagID::Субподрядчик)
-                    .ok()
-                    .map(|(row_adr, _)| (*row_adr, naimenov_rabot_i_zatr_adr.1)),
+                        .get(&TagID::Субподрядчик)
+                        .ok()
+                        .map(|(row_adr, _)| (*row_adr, naimenov_rabot_i_zatr_adr.1)),
                     "Исполнитель" => tag_address_map
                         .get(&TagID::Исполнитель)
                         .ok()
                         .map(|(row_adr, _)| (*row_adr, naimenov_rabot_i_zatr_adr.1)),
-                    "Глава" => {
-                        if is_valid_glava {
-                            Some((stroika_adr.0 + 1, stroika_adr.1))
-                        } else {
-                            None
-                        }
-                    }
-                    "Глава наименование" => {
-                        if is_valid_glava {
-                            Some((stroika_adr.0 + 1, naimenov_rabot_i_zatr_adr.1))
-                        } else {
-                            None
+                    "Глава" | "Глава наименование" => {
+                        let stroika_adr = tag_address_map.get(&TagID::Стройка)?;
+                        let object_adr = tag_address_map.get(&TagID::Объект)?;
+                        // Предполагается что строка с Главой должна четко находиться между "Стройкой" и "Объектом"
+                        let is_valid_glava = stroika_adr.0 + 2 == object_adr.0;
+
+                        match (is_valid_glava, *name) {
+                            (true, "Глава") => Some((stroika_adr.0 + 1, stroika_adr.1)),
+                            (true, _) => Some((stroika_adr.0 + 1, naimenov_rabot_i_zatr_adr.1)),
+                            (false, _) => None,
                         }
                     }
                     "Затраты труда, чел.-час" => {
@@ -164,17 +388,17 @@ impl Act {
                             err: None,
                         })
                     }
-                },
-            };
+                }
+            }
+        };
 
-            vec.push(temp_cells_address);
-        }
-        Ok(vec)
+        Ok(address)
     }
 
-    fn get_totals(
+    pub(super) fn get_totals(
         sheet: &Sheet,
         totals_start_adr: (usize, usize),
+        validation: &mut ValidationReport,
     ) -> Result<Vec<TotalsRow>, Error<'static>> {
         let (totals_start_row, totals_start_col) = totals_start_adr;
         let total_row = sheet.data.get_size().0;
@@ -187,12 +411,12 @@ impl Act {
         for row in totals_start_row..total_row {
             let row_data_type = &sheet.data[(row, totals_start_col)];
             if row_data_type.is_string() {
-                let base_price = &sheet.data[(row, base_col)];
-                let current_price = &sheet.data[(row, current_col)];
+                let base_price = totals_value(&sheet.data[(row, base_col)]);
+                let current_price = totals_value(&sheet.data[(row, current_col)]);
 
                 //Если пустых ячеек вместо имени еще не встречалось, то собираем данные независимо от наличия цены.
                 //Ситуация меняется если встретилось первое пустое имя: теперь потребуется и имя и цена (перестраховка на случай случайных пустых строк)
-                if !blank_row_flag || base_price.is_float() || current_price.is_float() {
+                if !blank_row_flag || base_price.is_some() || current_price.is_some() {
                     let row_name = row_data_type
                         .get_string()
                         .ok_or_else(|| Error::InternalLogic {
@@ -202,21 +426,37 @@ impl Act {
                         .trim()
                         .replace("\r\n", "");
 
+                    for (col, price) in [(base_col, &base_price), (current_col, &current_price)] {
+                        if let Some(XlDataType::Error(err)) = price {
+                            let a1_address = sheet.get_a1_address((row, col)).unwrap_or_default();
+                            validation.push(
+                                Severity::Warning,
+                                tr(
+                                    format!("Ячейка {a1_address} итоговой строки '{row_name}' содержит ошибку Excel {err}."),
+                                    format!("The cell {a1_address} of the totals row '{row_name}' contains the Excel error {err}."),
+                                ),
+                            );
+                        }
+                    }
+
                     match totals_row_vec
                         .iter_mut()
                         .find(|object| object.name == row_name)
                     {
                         Some(x) => {
-                            x.base_price.push(base_price.get_float());
-                            x.curr_price.push(current_price.get_float());
+                            x.base_price.push(base_price);
+                            x.curr_price.push(current_price);
                             x.row_number.push(sheet.range_start.0 + row + 1);
+                            x.original_names.push(row_name);
                         }
                         None => {
                             let temp_total_row = TotalsRow {
-                                name: row_name,
-                                base_price: vec![base_price.get_float()],
-                                curr_price: vec![current_price.get_float()],
+                                name: row_name.clone(),
+                                base_price: vec![base_price],
+                                curr_price: vec![current_price],
                                 row_number: vec![sheet.range_start.0 + row + 1],
+                                original_names: vec![row_name],
+                                is_percent: false,
                             };
                             totals_row_vec.push(temp_total_row);
                         }
@@ -231,6 +471,42 @@ impl Act {
     }
 }
 
+// Акт без данных для проверки записи отчета: шапка по DESIRED_CELLS_ARRAY пустая
+#[cfg(test)]
+impl Act {
+    pub fn for_test(path: &str, sheetname: &str) -> Act {
+        Act {
+            path: path.to_string(),
+            sheetname: sheetname.to_string(),
+            names_of_header: &DESIRED_CELLS_ARRAY,
+            data_of_header: vec![None; DESIRED_CELLS_ARRAY.len()],
+            data_of_totals: Vec::new(),
+            start_row_of_totals: 0,
+            validation: ValidationReport::default(),
+        }
+    }
+
+    pub fn set_header_value(&mut self, name: &str, value: XlDataType) {
+        let index = self
+            .names_of_header
+            .iter()
+            .position(|cell| cell.name == name)
+            .unwrap();
+        self.data_of_header[index] = Some(value);
+    }
+}
+
+// Значение цены в итогах акта: целые считаются числами, ошибки и логические значения сохраняются как есть
+fn totals_value(value: &Data) -> Option<XlDataType> {
+    match value {
+        Data::Float(number) => Some(XlDataType::Float(*number)),
+        Data::Int(number) => Some(XlDataType::Float(*number as f64)),
+        Data::Bool(value) => Some(XlDataType::Bool(*value)),
+        Data::Error(err) => Some(XlDataType::Error(err.to_string())),
+        _ => None,
+    }
+}
+
 fn calculate_cell_adr_by_coords(
     tag_address_map: &TagAddressMap,
     cell_coords: CellCoords,
@@ -287,3 +563,145 @@ fn calculate_cell_adr_by_coords(
         (Err(e), _) | (_, Err(e)) => Err(e),
     }
 }
+
+fn shift_address(address: (usize, usize), offset: (isize, isize)) -> Option<(usize, usize)> {
+    Some((
+        address.0.checked_add_signed(offset.0)?,
+        address.1.checked_add_signed(offset.1)?,
+    ))
+}
+
+// Общее смещение группы полей в пределах окна поиска. Значение засчитывается полю, если ячейка свободна
+// и значение подходит полю: в расчетном адресе по FieldType::fits, в сдвинутом - по более строгому is_match.
+// Если ни одно смещение не дает значений, поля остаются в расчетных адресах
+fn search_group_offset(
+    sheet: &Sheet,
+    group: &[(usize, (usize, usize))],
+    search: &NeighbourhoodSearch,
+    taken: &[(usize, usize)],
+) -> (isize, isize) {
+    let mut candidates: Vec<(isize, isize)> = (search.rows.0..=search.rows.1)
+        .flat_map(|row_ofst| {
+            (search.cols.0..=search.cols.1)
+                .map(move |col_ofst| (row_ofst as isize, col_ofst as isize))
+        })
+        .collect();
+    candidates.sort_by_key(|offset| (offset.0.abs() + offset.1.abs(), offset.0, offset.1));
+
+    let matches_count = |offset: (isize, isize)| {
+        group
+            .iter()
+            .filter(|(index, calculated)| {
+                let field_type = DESIRED_CELLS_ARRAY[*index].field_type;
+                shift_address(*calculated, offset).is_some_and(|adr| {
+                    !taken.contains(&sheet.resolve_merged_address(adr))
+                        && sheet.get_value(adr).is_some_and(|value| {
+                            if offset == (0, 0) {
+                                field_type.fits(value)
+                            } else {
+                                field_type.is_match(value)
+                            }
+                        })
+                })
+            })
+            .count()
+    };
+
+    let mut best = ((0, 0), 0);
+    for offset in candidates {
+        let count = matches_count(offset);
+        if count > best.1 {
+            best = (offset, count);
+        }
+    }
+    best.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Act, HeaderCellAddress, DESIRED_CELLS_ARRAY};
+    use crate::extract::sheet::Sheet;
+    use crate::extract::tags::{TagAddressMap, TagID};
+    use crate::extract::validation::ValidationReport;
+    use calamine::{Data, Range};
+    use std::path::PathBuf;
+
+    // Строка с номером и датами акта под тегом "Номер документа" (C11); dates_col - столбец даты акта
+    fn sheet_with_act_row(dates_col: u32) -> Sheet {
+        let mut data = Range::new((0, 0), (20, 20));
+        data.set_value((10, 2), Data::String("Номер документа".to_string()));
+        data.set_value((12, 2), Data::String("15".to_string()));
+        for (ofst, date) in ["31.07.2021", "01.07.2021", "31.07.2021"]
+            .iter()
+            .enumerate()
+        {
+            data.set_value(
+                (12, dates_col + ofst as u32),
+                Data::String(date.to_string()),
+            );
+        }
+
+        let mut tag_address_map = TagAddressMap::new();
+        tag_address_map.insert(TagID::НомерДокумента, (10, 2));
+
+        Sheet {
+            path: PathBuf::new(),
+            sheet_name: String::new(),
+            data,
+            tag_address_map,
+            range_start: (0, 0),
+            missing_tags: Vec::new(),
+            misplaced_tags: Vec::new(),
+            validation: ValidationReport::default(),
+            merged_regions: Vec::new(),
+        }
+    }
+
+    fn found_address(sheet: &Sheet, name: &str) -> Option<(usize, usize)> {
+        let index = DESIRED_CELLS_ARRAY
+            .iter()
+            .position(|cell| cell.name == name)
+            .unwrap();
+        match Act::locate_header_cells(sheet).remove(index) {
+            Ok(Some(HeaderCellAddress { found, .. })) => Some(found),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn locate_header_cells_with_shifted_row() {
+        // форма без изменений: значения в расчетных адресах
+        let sheet = sheet_with_act_row(6);
+        assert_eq!(found_address(&sheet, "Акт дата"), Some((12, 6)));
+        assert_eq!(
+            found_address(&sheet, "Отчетный период начало"),
+            Some((12, 7))
+        );
+        assert_eq!(
+            found_address(&sheet, "Отчетный период окончание"),
+            Some((12, 8))
+        );
+
+        // столбец вставлен между номером и датами: расчетный адрес даты акта пуст, а в расчетных
+        // адресах периода стоят соседние даты; строка должна сдвинуться целиком
+        let sheet = sheet_with_act_row(7);
+        assert_eq!(found_address(&sheet, "Акт №"), Some((12, 2)));
+        assert_eq!(found_address(&sheet, "Акт дата"), Some((12, 7)));
+        assert_eq!(
+            found_address(&sheet, "Отчетный период начало"),
+            Some((12, 8))
+        );
+        assert_eq!(
+            found_address(&sheet, "Отчетный период окончание"),
+            Some((12, 9))
+        );
+
+        // удаленный столбец сдвигает строку влево
+        let sheet = sheet_with_act_row(5);
+        assert_eq!(found_address(&sheet, "Акт дата"), Some((12, 5)));
+        assert_eq!(
+            found_address(&sheet, "Отчетный период окончание"),
+            Some((12, 7))
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use super::act::{Act, TotalsRow, DESIRED_CELLS_ARRAY};
+use super::validation::{Severity, ValidationReport};
+use crate::errors::Error;
+use crate::shared::types::{XlDataType, XlDate};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Временное хранилище собранных актов. Структура отчета рассчитывается только после разбора всех файлов,
+// поэтому акты до записи отчета откладываются в компактный двоичный файл, а не копятся в памяти.
+// Файл удаляется, когда хранилище (или читающий его итератор) больше не нужно
+pub struct ActStore {
+    file: TempFile,
+    writer: BufWriter<File>,
+    len: usize,
+}
+
+impl ActStore {
+    pub fn new() -> Result<ActStore, Error<'static>> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.subsec_nanos());
+        let path = std::env::temp_dir().join(format!("ks2_etl_{}_{nanos}.tmp", std::process::id()));
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|err| Error::ActStoreIo {
+                file_path: path.clone(),
+                err,
+            })?;
+
+        Ok(ActStore {
+            file: TempFile { path },
+            writer: BufWriter::new(file),
+            len: 0,
+        })
+    }
+
+    pub fn push(&mut self, act: &Act) -> Result<(), Error<'static>> {
+        write_act(&mut self.writer, act).map_err(|err| self.file.error(err))?;
+        self.len += 1;
+        Ok(())
+    }
+
+    // Акты читаются обратно по одному в порядке добавления
+    pub fn into_acts(mut self) -> Result<StoredActs, Error<'static>> {
+        self.writer.flush().map_err(|err| self.file.error(err))?;
+        drop(self.writer);
+
+        let reader = File::open(&self.file.path)
+            .map(BufReader::new)
+            .map_err(|err| self.file.error(err))?;
+
+        Ok(StoredActs {
+            file: self.file,
+            reader,
+            remaining: self.len,
+        })
+    }
+}
+
+pub struct StoredActs {
+    file: TempFile,
+    reader: BufReader<File>,
+    remaining: usize,
+}
+
+impl Iterator for StoredActs {
+    type Item = Result<Act, Error<'static>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(read_act(&mut self.reader).map_err(|err| self.file.error(err)))
+    }
+}
+
+// Акт в том же компактном виде, но в памяти: режим наблюдения держит разобранные акты между
+// пересборками отчета и заново разбирает только измененные файлы
+pub struct PackedAct(Vec<u8>);
+
+impl PackedAct {
+    pub fn new(act: &Act) -> PackedAct {
+        let mut bytes = Vec::new();
+        // запись в Vec не завершается ошибкой
+        let _ = write_act(&mut bytes, act);
+        PackedAct(bytes)
+    }
+
+    pub fn unpack(&self) -> Result<Act, Error<'static>> {
+        read_act(&mut self.0.as_slice()).map_err(|err| Error::InternalLogic {
+            tech_descr: "Не удалось восстановить акт, сохраненный в памяти".to_string(),
+            err: Some(Box::new(err)),
+        })
+    }
+}
+
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn error(&self, err: io::Error) -> Error<'static> {
+        Error::ActStoreIo {
+            file_path: self.path.clone(),
+            err,
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Формат записи: числа - little-endian, строки и векторы - с длиной впереди.
+// Шапка акта всегда соответствует DESIRED_CELLS_ARRAY и не сохраняется
+fn write_act(w: &mut impl Write, act: &Act) -> io::Result<()> {
+    write_str(w, &act.path)?;
+    write_str(w, &act.sheetname)?;
+    write_usize(w, act.start_row_of_totals)?;
+
+    write_usize(w, act.data_of_header.len())?;
+    for value in act.data_of_header.iter() {
+        write_value(w, value)?;
+    }
+
+    write_usize(w, act.data_of_totals.len())?;
+    for totals_row in act.data_of_totals.iter() {
+        write_str(w, &totals_row.name)?;
+        w.write_all(&[u8::from(totals_row.is_percent)])?;
+        write_usize(w, totals_row.row_number.len())?;
+        for instance in 0..totals_row.row_number.len() {
+            write_usize(w, totals_row.row_number[instance])?;
+            write_str(w, &totals_row.original_names[instance])?;
+            write_value(w, &totals_row.base_price[instance])?;
+            write_value(w, &totals_row.curr_price[instance])?;
+        }
+    }
+
+    write_usize(w, act.validation.issues().len())?;
+    for issue in act.validation.issues() {
+        let severity = match issue.severity {
+            Severity::Info => 0,
+            Severity::Warning => 1,
+            Severity::Error => 2,
+        };
+        w.write_all(&[severity])?;
+        write_str(w, &issue.message)?;
+    }
+    Ok(())
+}
+
+fn read_act(r: &mut impl Read) -> io::Result<Act> {
+    let path = read_str(r)?;
+    let sheetname = read_str(r)?;
+    let start_row_of_totals = read_usize(r)?;
+
+    let data_of_header = (0..read_usize(r)?)
+        .map(|_| read_value(r))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut data_of_totals = Vec::new();
+    for _ in 0..read_usize(r)? {
+        let name = read_str(r)?;
+        let is_percent = read_u8(r)? != 0;
+        let instance_count = read_usize(r)?;
+        let mut totals_row = TotalsRow {
+            name,
+            base_price: Vec::with_capacity(instance_count),
+            curr_price: Vec::with_capacity(instance_count),
+            row_number: Vec::with_capacity(instance_count),
+            original_names: Vec::with_capacity(instance_count),
+            is_percent,
+        };
+        for _ in 0..instance_count {
+            totals_row.row_number.push(read_usize(r)?);
+            totals_row.original_names.push(read_str(r)?);
+            totals_row.base_price.push(read_value(r)?);
+            totals_row.curr_price.push(read_value(r)?);
+        }
+        data_of_totals.push(totals_row);
+    }
+
+    let mut validation = ValidationReport::default();
+    for _ in 0..read_usize(r)? {
+        let severity = match read_u8(r)? {
+            0 => Severity::Info,
+            1 => Severity::Warning,
+            2 => Severity::Error,
+            _ => return Err(invalid_data()),
+        };
+        validation.push(severity, read_str(r)?);
+    }
+
+    Ok(Act {
+        path,
+        sheetname,
+        names_of_header: &DESIRED_CELLS_ARRAY,
+        data_of_header,
+        data_of_totals,
+        start_row_of_totals,
+        validation,
+    })
+}
+
+fn write_value(w: &mut impl Write, value: &Option<XlDataType>) -> io::Result<()> {
+    match value {
+        None => w.write_all(&[0]),
+        Some(XlDataType::String(text)) => {
+            w.write_all(&[1])?;
+            write_str(w, text)
+        }
+        Some(XlDataType::Float(number)) => {
+            w.write_all(&[2])?;
+            w.write_all(&number.to_le_bytes())
+        }
+        Some(XlDataType::Date(date)) => {
+            w.write_all(&[3])?;
+            w.write_all(&date.year.to_le_bytes())?;
+            w.write_all(&[date.month as u8, date.day as u8])
+        }
+        Some(XlDataType::Bool(value)) => w.write_all(&[4, u8::from(*value)]),
+        Some(XlDataType::Error(text)) => {
+            w.write_all(&[5])?;
+            write_str(w, text)
+        }
+        Some(XlDataType::Int(number)) => {
+            w.write_all(&[6])?;
+            w.write_all(&number.to_le_bytes())
+        }
+    }
+}
+
+fn read_value(r: &mut impl Read) -> io::Result<Option<XlDataType>> {
+    let value = match read_u8(r)? {
+        0 => return Ok(None),
+        1 => XlDataType::String(read_str(r)?),
+        2 => {
+            let mut bytes = [0; 8];
+            r.read_exact(&mut bytes)?;
+            XlDataType::Float(f64::from_le_bytes(bytes))
+        }
+        3 => {
+            let mut bytes = [0; 4];
+            r.read_exact(&mut bytes)?;
+            let year = i16::from_le_bytes([bytes[0], bytes[1]]);
+            let date =
+                XlDate::new(year, bytes[2] as i8, bytes[3] as i8).ok_or_else(invalid_data)?;
+            XlDataType::Date(date)
+        }
+        4 => XlDataType::Bool(read_u8(r)? != 0),
+        5 => XlDataType::Error(read_str(r)?),
+        6 => {
+            let mut bytes = [0; 8];
+            r.read_exact(&mut bytes)?;
+            XlDataType::Int(i64::from_le_bytes(bytes))
+        }
+        _ => return Err(invalid_data()),
+    };
+    Ok(Some(value))
+}
+
+fn write_usize(w: &mut impl Write, number: usize) -> io::Result<()> {
+    w.write_all(&(number as u64).to_le_bytes())
+}
+
+fn read_usize(r: &mut impl Read) -> io::Result<usize> {
+    let mut bytes = [0; 8];
+    r.read_exact(&mut bytes)?;
+    usize::try_from(u64::from_le_bytes(bytes)).map_err(|_| invalid_data())
+}
+
+fn read_u8(r: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0; 1];
+    r.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn write_str(w: &mut impl Write, text: &str) -> io::Result<()> {
+    write_usize(w, text.len())?;
+    w.write_all(text.as_bytes())
+}
+
+fn read_str(r: &mut impl Read) -> io::Result<String> {
+    let mut bytes = vec![0; read_usize(r)?];
+    r.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| invalid_data())
+}
+
+fn invalid_data() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "ks2_etl temporary act store is damaged",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActStore, PackedAct};
+    use crate::extract::act::{Act, TotalsRow, DESIRED_CELLS_ARRAY};
+    use crate::extract::validation::{Severity, ValidationReport};
+    use crate::shared::types::{XlDataType, XlDate};
+
+    #[test]
+    fn act_store_round_trip() {
+        let mut validation = ValidationReport::default();
+        validation.push(Severity::Warning, "предупреждение");
+
+        let mut data_of_header = vec![None; DESIRED_CELLS_ARRAY.len()];
+        data_of_header[0] = Some(XlDataType::String("Подрядчик".to_string()));
+        data_of_header[1] = Some(XlDataType::Date(XlDate::new(2021, 7, 31).unwrap()));
+        data_of_header[2] = Some(XlDataType::Error("#REF!".to_string()));
+        data_of_header[4] = Some(XlDataType::Int(-15));
+
+        let act = Act {
+            path: "акт.xlsm".to_string(),
+            sheetname: "Лист1".to_string(),
+            names_of_header: &DESIRED_CELLS_ARRAY,
+            data_of_header,
+            data_of_totals: vec![TotalsRow {
+                name: "Накладные расходы".to_string(),
+                base_price: vec![Some(XlDataType::Float(1.5)), None],
+                curr_price: vec![Some(XlDataType::Bool(true)), Some(XlDataType::Float(-2.))],
+                row_number: vec![40, 45],
+                original_names: vec!["НР".to_string(), "Накладные расходы".to_string()],
+                is_percent: false,
+            }],
+            start_row_of_totals: 38,
+            validation,
+        };
+
+        let mut store = ActStore::new().unwrap();
+        store.push(&act).unwrap();
+        store.push(&act).unwrap();
+
+        let acts: Vec<Act> = store.into_acts().unwrap().map(Result::unwrap).collect();
+        assert_eq!(acts.len(), 2);
+
+        let restored = &acts[1];
+        assert_eq!(restored.path, act.path);
+        assert_eq!(restored.start_row_of_totals, 38);
+        assert!(
+            matches!(&restored.data_of_header[1], Some(XlDataType::Date(date)) if date.day == 31)
+        );
+        assert!(
+            matches!(&restored.data_of_header[2], Some(XlDataType::Error(text)) if text == "#REF!")
+        );
+        assert!(restored.data_of_header[3].is_none());
+        assert!(matches!(
+            restored.data_of_header[4],
+            Some(XlDataType::Int(-15))
+        ));
+
+        let totals_row = &restored.data_of_totals[0];
+        assert_eq!(totals_row.row_number, vec![40, 45]);
+        assert_eq!(totals_row.original_names[0], "НР");
+        assert!(totals_row.base_price[1].is_none());
+        assert!(matches!(totals_row.curr_price[1], Some(XlDataType::Float(x)) if x == -2.));
+        assert_eq!(restored.validation.issues()[0].severity, Severity::Warning);
+
+        let unpacked = PackedAct::new(&act).unpack().unwrap();
+        assert_eq!(
+            unpacked.data_of_totals[0].original_names,
+            totals_row.original_names
+        );
+        assert_eq!(unpacked.validation.issues().len(), 1);
+    }
+}
@@ -1,10 +1,15 @@
-use crate::shared::constants::XL_FILE_EXTENSION;
 use crate::errors::Error;
+use crate::shared::constants::{IGNORE_FILE_NAME, MANIFEST_FILE_EXTENSIONS, XL_FILE_EXTENSION};
+use crate::shared::lang::tr;
 use crate::ui;
 use calamine::Xlsx;
-use std::fs::File;
+use globset::{GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::HashMap;
+use std::fs::{self, File};
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 pub struct Book {
@@ -25,27 +30,298 @@ impl Book {
     }
 }
 
+// Параметры отбора файлов при обходе папки (ключи файла настроек "include", "exclude", "exclude_marker",
+// "max_depth", "follow_symlinks"). Шаблоны glob сравниваются с путем файла относительно указанной папки
+// с разделителем '/' и без учета регистра: "2023/**", "**/черновики/**", "*.xlsm"
+#[derive(Debug, Clone)]
+pub struct DiscoveryOptions {
+    pub include: Vec<String>, // файл собирается, если подходит хотя бы под один шаблон; пусто - все файлы
+    pub exclude: Vec<String>, // файл не собирается, если подходит хотя бы под один шаблон
+    pub exclusion_marker: String, // символы в имени файла или папки, исключающие их из сбора; пусто - не используется
+    pub max_depth: Option<usize>, // уровней вложенных папок; 0 - только файлы самой папки
+    pub follow_symlinks: bool,
+    pub sheet_names: Vec<(String, String)>, // (шаблон пути, лист): лист для файлов, подходящих под шаблон
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        DiscoveryOptions {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            exclusion_marker: "@".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            sheet_names: Vec::new(),
+        }
+    }
+}
+
+impl DiscoveryOptions {
+    pub fn is_valid_glob(pattern: &str) -> bool {
+        glob(pattern).is_ok()
+    }
+
+    // Отличия от отбора по умолчанию для сводки перед сбором; None, если их нет
+    pub fn describe(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        if !self.include.is_empty() {
+            lines.push(tr(
+                format!("только по шаблонам: {}", self.include.join(", ")),
+                format!("only matching: {}", self.include.join(", ")),
+            ));
+        }
+        if !self.exclude.is_empty() {
+            lines.push(tr(
+                format!("кроме шаблонов: {}", self.exclude.join(", ")),
+                format!("except matching: {}", self.exclude.join(", ")),
+            ));
+        }
+        if let Some(max_depth) = self.max_depth {
+            lines.push(tr(
+                format!("уровней вложенных папок не более {max_depth}"),
+                format!("at most {max_depth} levels of subfolders"),
+            ));
+        }
+        if self.follow_symlinks {
+            lines.push(
+                tr(
+                    "с переходом по символическим ссылкам",
+                    "following symbolic links",
+                )
+                .to_string(),
+            );
+        }
+        if self.exclusion_marker != DiscoveryOptions::default().exclusion_marker {
+            lines.push(if self.exclusion_marker.is_empty() {
+                tr(
+                    "пометка для исключения не используется",
+                    "no exclusion mark",
+                )
+                .to_string()
+            } else {
+                tr(
+                    format!(r#"пометка для исключения "{}""#, self.exclusion_marker),
+                    format!(r#"exclusion mark "{}""#, self.exclusion_marker),
+                )
+            });
+        }
+
+        for (pattern, sheet_name) in &self.sheet_names {
+            lines.push(tr(
+                format!(r#"лист "{sheet_name}" для "{pattern}""#),
+                format!(r#"sheet "{sheet_name}" for "{pattern}""#),
+            ));
+        }
+
+        (!lines.is_empty()).then(|| {
+            let title = tr("Отбор файлов:", "File selection:");
+            format!("{title} {}.", lines.join("; "))
+        })
+    }
+}
+
+// Регистр не учитывается за счет приведения шаблона и пути к нижнему регистру:
+// собственный режим globset без учета регистра не распространяется на кириллицу
+fn glob(pattern: &str) -> Result<globset::Glob, globset::Error> {
+    GlobBuilder::new(&pattern.to_lowercase())
+        .literal_separator(true)
+        .build()
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet, Error<'static>> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(glob(pattern).map_err(|err| Error::InternalLogic {
+            tech_descr: format!(
+                "Шаблон отбора файлов '{pattern}' не прошел проверку при чтении настроек"
+            ),
+            err: Some(Box::new(err)),
+        })?);
+    }
+    builder.build().map_err(|err| Error::InternalLogic {
+        tech_descr: "Не удалось составить набор шаблонов отбора файлов".to_string(),
+        err: Some(Box::new(err)),
+    })
+}
+
+// Листы по шаблонам пути для архивов, где подрядчики называют лист акта по-разному.
+// Шаблон без "/" сравнивается с именем файла и с именем каждой папки на пути к нему,
+// шаблон с "/" - со всем путем от введенной папки. Действует первое подходящее правило
+struct SheetNameRules {
+    rules: Vec<(GlobMatcher, bool, String)>, // (шаблон, сравнивать по частям пути, лист)
+}
+
+impl SheetNameRules {
+    fn new(sheet_names: &[(String, String)]) -> Result<Self, Error<'static>> {
+        let mut rules = Vec::new();
+        for (pattern, sheet_name) in sheet_names {
+            let matcher = glob(pattern)
+                .map_err(|err| Error::InternalLogic {
+                    tech_descr: format!(
+                        "Шаблон выбора листа '{pattern}' не прошел проверку при чтении настроек"
+                    ),
+                    err: Some(Box::new(err)),
+                })?
+                .compile_matcher();
+            rules.push((matcher, !pattern.contains('/'), sheet_name.clone()));
+        }
+        Ok(SheetNameRules { rules })
+    }
+
+    // Путь - с разделителями "/" и в нижнем регистре, как для шаблонов отбора
+    fn sheet_name(&self, glob_path: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|(matcher, by_part, _)| {
+                if *by_part {
+                    glob_path.split('/').any(|part| matcher.is_match(part))
+                } else {
+                    matcher.is_match(glob_path)
+                }
+            })
+            .map(|(_, _, sheet_name)| sheet_name.as_str())
+    }
+}
+
+// Правила файлов .ks2ignore (синтаксис .gitignore) по папкам, где они найдены
+#[derive(Default)]
+struct IgnoreRules {
+    by_dir: HashMap<PathBuf, Gitignore>,
+}
+
+impl IgnoreRules {
+    fn add_dir(&mut self, dir: &Path) {
+        let ignore_path = dir.join(IGNORE_FILE_NAME);
+        if !ignore_path.is_file() {
+            return;
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        // строки с ошибками пропускаются, остальные правила действуют
+        let _ = builder.add(&ignore_path);
+        if let Ok(gitignore) = builder.build() {
+            self.by_dir.insert(dir.to_path_buf(), gitignore);
+        }
+    }
+
+    // Как в git: решает ближайший к файлу .ks2ignore, в котором файл (или его папка) упомянут,
+    // поэтому вложенный файл может вернуть в сбор исключенное выше правилом "!шаблон"
+    fn is_ignored(&self, file_path: &Path, root: &Path) -> bool {
+        for dir in file_path.ancestors().skip(1) {
+            if let Some(gitignore) = self.by_dir.get(dir) {
+                match gitignore.matched_path_or_any_parents(file_path, false) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => (),
+                }
+            }
+            if dir == root {
+                break;
+            }
+        }
+        false
+    }
+}
+
+// Элемент дерева папок, пропущенный при поиске: сбор по такой папке может оказаться неполным
+#[derive(Debug, Clone)]
+pub struct SkippedEntry {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+// Отобранные файлы. Книги открываются по одной уже при сборе, чтобы не держать открытыми все сразу
 pub struct ExtractedBooks {
-    pub books: Vec<Result<Book, Error<'static>>>,
-    pub file_count_excluded: usize,
+    pub file_paths: Vec<PathBuf>,
+    pub file_count_excluded: usize, // помечены для исключения
+    pub file_count_filtered: usize, // не подходят под шаблоны отбора
+    pub file_count_ignored: usize,  // исключены правилами .ks2ignore
+    pub skipped: Vec<SkippedEntry>, // не удалось прочитать или разобрать имя
+    // листы, отличные от введенного: из строки списка к сбору или по шаблонам "sheet_name" настроек
+    pub sheet_names: HashMap<PathBuf, String>,
 }
 
 impl ExtractedBooks {
-    pub fn new(path: &PathBuf) -> Result<Self, Error<'static>> {
-        let files: Vec<_> = WalkDir::new(path)
-            .into_iter()
-            .filter_map(|e| e.ok()) //будет молча пропускать каталоги, на доступ к которым у владельца запущенного процесса нет разрешения
-            .filter(|e| {
-                e.file_name()
-                    .to_str()
-                    .map(|s| !s.starts_with('~') & s.ends_with(XL_FILE_EXTENSION))
-                    .unwrap_or_else(|| false)
-            })
-            .collect();
+    pub fn new(path: &PathBuf, options: &DiscoveryOptions) -> Result<Self, Error<'static>> {
+        Self::discover(path, options, true)
+    }
+
+    // Тот же отбор без вывода списка файлов в консоль: для повторных проходов режима наблюдения
+    pub fn scan(path: &PathBuf, options: &DiscoveryOptions) -> Result<Self, Error<'static>> {
+        Self::discover(path, options, false)
+    }
+
+    fn discover(
+        path: &PathBuf,
+        options: &DiscoveryOptions,
+        display: bool,
+    ) -> Result<Self, Error<'static>> {
+        let include = glob_set(&options.include)?;
+        let exclude = glob_set(&options.exclude)?;
+        let sheet_rules = SheetNameRules::new(&options.sheet_names)?;
+
+        // порядок папок на диске зависит от файловой системы; от порядка актов зависит порядок
+        // столбцов новых итоговых строк, поэтому он должен быть одинаковым на любой машине
+        let mut walk_dir = WalkDir::new(path)
+            .follow_links(options.follow_symlinks)
+            .sort_by_file_name();
+        if let Some(max_depth) = options.max_depth {
+            // глубина 1 у WalkDir - файлы самой папки
+            walk_dir = walk_dir.max_depth(max_depth + 1);
+        }
+
+        // WalkDir выдает папку раньше ее содержимого, так что к проверке файлов правила всех папок уже собраны
+        let mut ignore_rules = IgnoreRules::default();
+        let mut skipped = Vec::new();
+        let mut files = Vec::new();
+        for entry in walk_dir {
+            // папки без разрешения на чтение, циклы символических ссылок и прочие ошибки обхода
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    let path = err.path().map(Path::to_path_buf).unwrap_or_default();
+                    skipped.push(SkippedEntry {
+                        path,
+                        reason: tr(
+                            format!("Не удалось прочитать при поиске файлов: {err}"),
+                            format!("Could not be read while searching for files: {err}"),
+                        ),
+                    });
+                    continue;
+                }
+            };
+            if entry.file_type().is_dir() {
+                ignore_rules.add_dir(entry.path());
+                continue;
+            }
+            let Some(file_name) = entry.file_name().to_str() else {
+                // имя не в Юникоде: по расширению видно, что это мог быть акт
+                if entry
+                    .file_name()
+                    .to_string_lossy()
+                    .ends_with(XL_FILE_EXTENSION)
+                {
+                    skipped.push(SkippedEntry {
+                        path: entry.into_path(),
+                        reason: tr(
+                            "Имя файла содержит символы вне Юникода, файл не собран",
+                            "The file name contains non-Unicode characters, the file was not collected",
+                        )
+                        .to_string(),
+                    });
+                }
+                continue;
+            };
+            if !file_name.starts_with('~') && file_name.ends_with(XL_FILE_EXTENSION) {
+                files.push(entry);
+            }
+        }
 
         let mut xl_files_vec = vec![];
         let mut file_count_excluded = 0;
+        let mut file_count_filtered = 0;
+        let mut file_count_ignored = 0;
         let mut file_print_counter = 0;
+        let mut sheet_names = HashMap::new();
 
         for entry in files {
             let file_checked_path = entry
@@ -59,15 +335,48 @@ impl ExtractedBooks {
                     ),
                     err: Some(Box::new(err)),
                 })?
-                .to_string_lossy();
+                .to_string_lossy()
+                .replace('\\', "/");
+            let file_glob_path = file_checked_path.to_lowercase();
 
-            if path.is_dir() && file_checked_path.contains('@') {
+            if path.is_dir()
+                && !options.exclusion_marker.is_empty()
+                && file_checked_path.contains(options.exclusion_marker.as_str())
+            {
                 file_count_excluded += 1;
                 continue;
             }
 
+            if path.is_dir() && ignore_rules.is_ignored(entry.path(), path) {
+                file_count_ignored += 1;
+                continue;
+            }
+
+            if path.is_dir()
+                && ((!options.include.is_empty() && !include.is_match(&file_glob_path))
+                    || exclude.is_match(&file_glob_path))
+            {
+                file_count_filtered += 1;
+                continue;
+            }
+
+            // для одного файла путь от введенной папки пуст, шаблоны сравниваются с именем файла
+            let sheet_glob_path = if path.is_dir() {
+                file_glob_path.clone()
+            } else {
+                entry.file_name().to_string_lossy().to_lowercase()
+            };
+            if let Some(sheet_name) = sheet_rules.sheet_name(&sheet_glob_path) {
+                sheet_names.insert(entry.path().to_path_buf(), sheet_name.to_string());
+            }
+
+            if !display {
+                xl_files_vec.push(entry.into_path());
+                continue;
+            }
+
             if xl_files_vec.is_empty() {
-                ui::display_formatted_text("\nОтбранны файлы:", None);
+                ui::display_formatted_text(tr("\nОтбранны файлы:", "\nSelected files:"), None);
             }
 
             // .is_dir() нужен в виду того что путь к файлу вернет по .parent() весь путь включая ближайшую папку
@@ -98,13 +407,307 @@ impl ExtractedBooks {
             let msg = format!("{}: {}", file_print_counter, file_display_path);
             ui::display_formatted_text(&msg, None);
 
-            let xl_file = Book::new(entry.into_path());
-            xl_files_vec.push(xl_file);
+            xl_files_vec.push(entry.into_path());
         }
 
         Ok(Self {
-            books: xl_files_vec,
+            file_paths: xl_files_vec,
             file_count_excluded,
+            file_count_filtered,
+            file_count_ignored,
+            skipped,
+            sheet_names,
         })
     }
+
+    pub fn is_manifest(path: &Path) -> bool {
+        let file_name = path.to_string_lossy().to_lowercase();
+        path.is_file()
+            && MANIFEST_FILE_EXTENSIONS
+                .iter()
+                .any(|extension| file_name.ends_with(extension))
+    }
+
+    // Сбор по списку файлов вместо папки: строка списка - путь к файлу и, через ";" или табуляцию,
+    // необязательное имя листа. Относительные пути отсчитываются от папки списка.
+    // Из настроек отбора к списку применяются только листы по шаблонам. Ненайденные файлы, файлы не ".xlsm"
+    // и повторы одного файла попадают в пропущенные
+    pub fn from_manifest(
+        manifest_path: &Path,
+        options: &DiscoveryOptions,
+    ) -> Result<Self, Error<'static>> {
+        Self::read_manifest(manifest_path, options, true)
+    }
+
+    // Тот же список без вывода файлов в консоль: список перечитывается при каждом опросе наблюдения
+    pub fn scan_manifest(
+        manifest_path: &Path,
+        options: &DiscoveryOptions,
+    ) -> Result<Self, Error<'static>> {
+        Self::read_manifest(manifest_path, options, false)
+    }
+
+    fn read_manifest(
+        manifest_path: &Path,
+        options: &DiscoveryOptions,
+        display: bool,
+    ) -> Result<Self, Error<'static>> {
+        let sheet_rules = SheetNameRules::new(&options.sheet_names)?;
+        let text = fs::read_to_string(manifest_path).map_err(|err| Error::ManifestRead {
+            file_path: manifest_path.to_path_buf(),
+            err,
+        })?;
+        let base_dir = manifest_path.parent().unwrap_or(Path::new(""));
+
+        let mut file_paths = Vec::new();
+        let mut skipped = Vec::new();
+        let mut sheet_names = HashMap::new();
+        let mut listed_lines: HashMap<PathBuf, usize> = HashMap::new(); // строка списка по пути к файлу
+
+        for (line_index, line) in text.trim_start_matches('\u{FEFF}').lines().enumerate() {
+            let Some((entered_path, sheet_name)) = parse_manifest_line(line) else {
+                continue;
+            };
+            // строка заголовка, если список выгружен из таблицы
+            if line_index == 0
+                && MANIFEST_HEADER_TITLES.contains(&entered_path.to_lowercase().as_str())
+            {
+                continue;
+            }
+            let file_path = base_dir.join(&entered_path);
+            let line_number = line_index + 1;
+            if !file_path.is_file() {
+                skipped.push(SkippedEntry {
+                    path: file_path,
+                    reason: tr(
+                        format!("Файл из списка не найден (строка {line_number})"),
+                        format!("The file from the list was not found (line {line_number})"),
+                    ),
+                });
+                continue;
+            }
+            // те же файлы, что отбираются при обходе папки: книги ".xlsm", кроме временных копий Excel
+            let is_xl_file = file_path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .is_some_and(|file_name| {
+                    !file_name.starts_with('~') && file_name.ends_with(XL_FILE_EXTENSION)
+                });
+            if !is_xl_file {
+                skipped.push(SkippedEntry {
+                    path: file_path,
+                    reason: tr(
+                        format!("Файл из списка не является книгой «{XL_FILE_EXTENSION}» (строка {line_number})"),
+                        format!("The file from the list is not a «{XL_FILE_EXTENSION}» workbook (line {line_number})"),
+                    ),
+                });
+                continue;
+            }
+            // один файл может быть записан по-разному: относительным и абсолютным путем, в другом регистре
+            let canonical_path = fs::canonicalize(&file_path).unwrap_or_else(|_| file_path.clone());
+            if let Some(first_line_number) = listed_lines.get(&canonical_path) {
+                skipped.push(SkippedEntry {
+                    path: file_path,
+                    reason: tr(
+                        format!("Файл уже указан в строке {first_line_number} списка (строка {line_number})"),
+                        format!("The file is already listed in line {first_line_number} (line {line_number})"),
+                    ),
+                });
+                continue;
+            }
+            listed_lines.insert(canonical_path, line_number);
+
+            if display {
+                if file_paths.is_empty() {
+                    ui::display_formatted_text(tr("\nОтбранны файлы:", "\nSelected files:"), None);
+                }
+                let msg = format!("{}: {}", file_paths.len() + 1, entered_path);
+                ui::display_formatted_text(&msg, None);
+            }
+
+            // лист, указанный в строке списка, важнее шаблонов из настроек
+            let sheet_glob_path = entered_path.replace('\\', "/").to_lowercase();
+            let sheet_name =
+                sheet_name.or_else(|| sheet_rules.sheet_name(&sheet_glob_path).map(str::to_string));
+            if let Some(sheet_name) = sheet_name {
+                sheet_names.insert(file_path.clone(), sheet_name);
+            }
+            file_paths.push(file_path);
+        }
+
+        Ok(Self {
+            file_paths,
+            file_count_excluded: 0,
+            file_count_filtered: 0,
+            file_count_ignored: 0,
+            skipped,
+            sheet_names,
+        })
+    }
+}
+
+// Заголовки первого столбца, по которым узнается строка заголовка списка
+const MANIFEST_HEADER_TITLES: [&str; 4] = ["путь", "файл", "path", "file"];
+
+// Путь и необязательное имя листа; пустые строки и строки-комментарии ("#") пропускаются.
+// Поле в кавычках может содержать разделитель, кавычки внутри него удваиваются
+fn parse_manifest_line(line: &str) -> Option<(String, Option<String>)> {
+    if line.trim().is_empty() || line.trim_start().starts_with('#') {
+        return None;
+    }
+
+    let mut fields = vec![String::new()];
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut()?.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ';' | '\t' if !in_quotes => fields.push(String::new()),
+            ch => fields.last_mut()?.push(ch),
+        }
+    }
+
+    let mut fields = fields.into_iter().map(|field| field.trim().to_string());
+    let path = fields.next().filter(|path| !path.is_empty())?;
+    let sheet_name = fields.next().filter(|sheet_name| !sheet_name.is_empty());
+    Some((path, sheet_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        glob_set, parse_manifest_line, DiscoveryOptions, ExtractedBooks, IgnoreRules,
+        SheetNameRules,
+    };
+    use crate::settings::Settings;
+    use crate::shared::constants::IGNORE_FILE_NAME;
+    use std::fs;
+
+    #[test]
+    fn discovery_glob_test() {
+        let include = glob_set(&["2023/**".to_string(), "*.xlsm".to_string()]).unwrap();
+        assert!(include.is_match("2023/май/акт.xlsm"));
+        assert!(include.is_match("акт.xlsm"));
+        // '*' не переходит в другую папку
+        assert!(!include.is_match("2024/акт.xlsm"));
+
+        let exclude = glob_set(&["**/Черновики/**".to_string()]).unwrap();
+        assert!(exclude.is_match("2023/черновики/акт.xlsm"));
+        assert!(!exclude.is_match("2023/акт.xlsm"));
+    }
+
+    #[test]
+    fn ignore_rules_test() {
+        let root = std::env::temp_dir().join(format!("ks2_etl_ignore_{}", std::process::id()));
+        let nested = root.join("2023");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join(IGNORE_FILE_NAME), "черновик*.xlsm\nархив/\n").unwrap();
+        fs::write(nested.join(IGNORE_FILE_NAME), "!черновик-итог.xlsm\n").unwrap();
+
+        let mut rules = IgnoreRules::default();
+        rules.add_dir(&root);
+        rules.add_dir(&nested);
+
+        assert!(rules.is_ignored(&root.join("черновик 1.xlsm"), &root));
+        assert!(rules.is_ignored(&root.join("архив").join("акт.xlsm"), &root));
+        assert!(rules.is_ignored(&nested.join("черновик 2.xlsm"), &root));
+        assert!(!rules.is_ignored(&nested.join("черновик-итог.xlsm"), &root));
+        assert!(!rules.is_ignored(&nested.join("акт.xlsm"), &root));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn manifest_line_test() {
+        assert_eq!(parse_manifest_line("  # комментарий"), None);
+        assert_eq!(parse_manifest_line(" ; КС-2"), None);
+        assert_eq!(
+            parse_manifest_line(r"2023\акт 1.xlsm"),
+            Some((r"2023\акт 1.xlsm".to_string(), None))
+        );
+        assert_eq!(
+            parse_manifest_line("акт 2.xlsm\tКС-2"),
+            Some(("акт 2.xlsm".to_string(), Some("КС-2".to_string())))
+        );
+        assert_eq!(
+            parse_manifest_line(r#""акт; ред. ""2"".xlsm"; Лист1;"#),
+            Some((
+                r#"акт; ред. "2".xlsm"#.to_string(),
+                Some("Лист1".to_string())
+            ))
+        );
+    }
+
+    #[test]
+    fn manifest_filter_test() {
+        let root = std::env::temp_dir().join(format!("ks2_etl_manifest_{}", std::process::id()));
+        fs::create_dir_all(root.join("2023")).unwrap();
+        for file_name in ["2023/акт 1.xlsm", "акт 2.xlsx", "~$акт 3.xlsm"] {
+            fs::write(root.join(file_name), "").unwrap();
+        }
+        let manifest_path = root.join("список.txt");
+        let absolute_path = root.join("2023").join("акт 1.xlsm");
+        let text = format!(
+            "путь\n2023/акт 1.xlsm\n{}\n2023/../2023/акт 1.xlsm\nакт 2.xlsx\n~$акт 3.xlsm\nакт 4.xlsm\n",
+            absolute_path.display()
+        );
+        fs::write(&manifest_path, text).unwrap();
+
+        let books =
+            ExtractedBooks::scan_manifest(&manifest_path, &DiscoveryOptions::default()).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(books.file_paths, vec![root.join("2023/акт 1.xlsm")]);
+        let skipped_lines = books
+            .skipped
+            .iter()
+            .map(|entry| entry.reason.rsplit(' ').next().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(skipped_lines, ["3)", "4)", "5)", "6)", "7)"]);
+    }
+
+    #[test]
+    fn sheet_name_rules_test() {
+        let rules = SheetNameRules::new(&[
+            ("Подрядчик А".to_string(), "КС-2".to_string()),
+            ("*ред*.xlsm".to_string(), "Акт".to_string()),
+            ("2023/**".to_string(), "Лист2".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(rules.sheet_name("подрядчик а/май/акт 1.xlsm"), Some("КС-2"));
+        assert_eq!(rules.sheet_name("2023/акт 2 ред.xlsm"), Some("Акт"));
+        assert_eq!(rules.sheet_name("2023/май/акт 3.xlsm"), Some("Лист2"));
+        assert_eq!(rules.sheet_name("2024/акт 4.xlsm"), None);
+    }
+
+    #[test]
+    fn discovery_settings() {
+        let settings = Settings::from_text(
+            "include = 2023/**\nexclude = **/черновики/**\nexclude_marker =\nmax_depth = 2\nfollow_symlinks = да",
+        )
+        .unwrap();
+        assert_eq!(settings.discovery.include, vec!["2023/**"]);
+        assert_eq!(settings.discovery.exclude, vec!["**/черновики/**"]);
+        assert_eq!(settings.discovery.exclusion_marker, "");
+        assert_eq!(settings.discovery.max_depth, Some(2));
+        assert!(settings.discovery.follow_symlinks);
+        assert!(Settings::from_text("include = [").is_err());
+        assert!(Settings::from_text("max_depth = -1").is_err());
+        assert!(Settings::from_text("follow_symlinks = иногда").is_err());
+    }
+
+    #[test]
+    fn sheet_name_setting() {
+        let settings = Settings::from_text("sheet_name = **/Подрядчик А/** => КС-2").unwrap();
+        assert_eq!(
+            settings.discovery.sheet_names,
+            vec![("**/Подрядчик А/**".to_string(), "КС-2".to_string())]
+        );
+        assert!(Settings::from_text("sheet_name = Подрядчик Б").is_err());
+        assert!(Settings::from_text("sheet_name = [ => КС-2").is_err());
+    }
 }
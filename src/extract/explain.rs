@@ -0,0 +1,143 @@
+use super::act::{Act, HeaderCellAddress, DESIRED_CELLS_ARRAY};
+use super::books::Book;
+use super::sheet::Sheet;
+use super::tags::{TagID, TAG_INFO_ARRAY};
+use super::validation::ValidationReport;
+use crate::errors::Error;
+use crate::shared::lang::tr;
+
+// Диагностический разбор одного файла: где найдены теги, по каким адресам вычислены ячейки шапки
+// и какие строки итогов обнаружены. В отличие от сбора не прерывается на ошибке валидации листа,
+// а показывает все найденное и в конце саму ошибку.
+pub fn explain<'a>(book: Book, user_entered_sh_name: &'a str) -> Result<String, Error<'a>> {
+    let sheet = Sheet::new_unverified(book, user_entered_sh_name)?;
+    let not_found = tr("не найдено", "not found");
+    let mut lines = vec![
+        format!("{} {}", tr("Файл:", "File:"), sheet.path.display()),
+        format!("{} '{}'", tr("Лист:", "Sheet:"), sheet.sheet_name),
+        String::new(),
+        tr("Ключевые слова (теги):", "Keywords (tags):").to_string(),
+    ];
+
+    for tag_info in TAG_INFO_ARRAY {
+        let misplaced_adr = sheet
+            .misplaced_tags
+            .iter()
+            .find(|(tag_id, _)| *tag_id == tag_info.id)
+            .map(|(_, adr)| adr);
+
+        let address = match (sheet.tag_address_map.get(&tag_info.id), misplaced_adr) {
+            (Ok(adr), _) => sheet.get_a1_address(*adr)?,
+            (Err(_), Some(adr)) => format!(
+                "{} {}",
+                sheet.get_a1_address(*adr)?,
+                tr(
+                    "(вне установленного порядка)",
+                    "(out of the required order)"
+                )
+            ),
+            (Err(_), None) => not_found.to_string(),
+        };
+        let required_mark = if tag_info.is_required { "*" } else { " " };
+        lines.push(format!(
+            "  {required_mark} {:<42} {address}",
+            tag_info.id.as_str()
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(tr("Ячейки шапки акта:", "Act header cells:").to_string());
+
+    let header_cells = Act::locate_header_cells(&sheet);
+    for (desired_cell, located) in DESIRED_CELLS_ARRAY.iter().zip(header_cells) {
+        let (address, raw_value) = match located {
+            Ok(Some(HeaderCellAddress { calculated, found })) => {
+                let mut address = sheet.get_a1_address(found)?;
+                let origin = sheet.resolve_merged_address(found);
+                if origin != found {
+                    address += &format!(
+                        " ({} {})",
+                        tr("объединена с", "merged into"),
+                        sheet.get_a1_address(origin)?
+                    );
+                }
+                if calculated != found {
+                    address += &format!(
+                        " ({} {})",
+                        tr(
+                            "найдено поиском, расчетный адрес",
+                            "found by search, calculated address"
+                        ),
+                        sheet.get_a1_address(calculated)?
+                    );
+                }
+                let raw_value = match sheet.get_value(found) {
+                    Some(value) => format!("{:?}", value),
+                    None => tr("вне диапазона листа", "outside the sheet range").to_string(),
+                };
+                (address, raw_value)
+            }
+            Ok(None) => (not_found.to_string(), String::new()),
+            Err(_) => (
+                tr("не вычислен", "not calculated").to_string(),
+                tr("(нет нужных тегов)", "(required tags are missing)").to_string(),
+            ),
+        };
+        lines.push(format!(
+            "    {:<30} {:<8} {raw_value}",
+            desired_cell.name, address
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(tr("Строки итогов акта:", "Act totals rows:").to_string());
+
+    match sheet
+        .tag_address_map
+        .get(&TagID::СтоимостьМатериальныхРесурсовВсего)
+    {
+        Ok(start_adr) => {
+            match Act::get_totals(&sheet, *start_adr, &mut ValidationReport::default()) {
+                Ok(totals) => {
+                    for totals_row in totals {
+                        let row_numbers = totals_row
+                            .row_number
+                            .iter()
+                            .map(|number| number.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        lines.push(format!(
+                            "    {} {:<60} {:?} {:?}",
+                            tr("стр.", "row"),
+                            format!("{row_numbers}: {}", totals_row.name),
+                            totals_row.base_price,
+                            totals_row.curr_price
+                        ));
+                    }
+                }
+                Err(err) => lines.push(format!("    {err}")),
+            }
+        }
+        Err(_) => lines.push(format!("    {not_found}")),
+    }
+
+    lines.push(String::new());
+    lines.push(tr("Результат проверки листа:", "Sheet validation result:").to_string());
+    match sheet.verify() {
+        Ok(report) if report.issues().is_empty() => {
+            lines.push(tr("    ошибок не обнаружено.", "    no errors found.").to_string())
+        }
+        Ok(report) => {
+            for issue in report.issues() {
+                lines.push(format!(
+                    "    [{}] {}",
+                    issue.severity.as_str(),
+                    issue.message
+                ));
+            }
+        }
+        Err(err) => lines.push(err.to_string()),
+    }
+
+    Ok(lines.join("\n"))
+}
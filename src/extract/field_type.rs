@@ -0,0 +1,264 @@
+use crate::shared::lang::tr;
+use crate::shared::types::{XlDataType, XlDate};
+use calamine::Data;
+
+// Тип значения, которое ожидается в ячейке шапки акта
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldType {
+    Text,
+    Date,
+    Money,     // сумма в тысячах рублей текстом вида "13,797 тыс. руб.", собирается в рублях
+    Number,    // дробное число, например трудозатраты
+    Integer,   // целое число: число без дробной части или текст из цифр
+    DocNumber, // номер документа вида "15", "№ 40/26-Д", "Смета № 02-02-01": см. is_doc_number
+}
+
+impl FieldType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FieldType::Text => tr("текст", "text"),
+            FieldType::Date => tr("дата", "date"),
+            FieldType::Money => tr("денежная сумма", "money"),
+            FieldType::Number => tr("число", "number"),
+            FieldType::Integer => tr("целое число", "integer"),
+            FieldType::DocNumber => tr("номер документа", "document number"),
+        }
+    }
+
+    // Приведение значения ячейки к типу поля. None для непустой ячейки означает несоответствие типу
+    pub fn convert(&self, value: &Data) -> Option<XlDataType> {
+        match (self, value) {
+            (_, value) if is_blank(value) => None,
+            // ошибка Excel сохраняется при любом типе поля, чтобы не пропасть без следа
+            (_, Data::Error(err)) => Some(XlDataType::Error(err.to_string())),
+
+            (FieldType::Text, Data::String(text)) => Some(XlDataType::String(clean_text(text))),
+            (FieldType::Text, Data::Float(number)) => Some(XlDataType::String(number.to_string())),
+            (FieldType::Text, Data::Int(number)) => Some(XlDataType::String(number.to_string())),
+            (FieldType::Text, Data::Bool(value)) => Some(XlDataType::Bool(*value)),
+            // дата в текстовом поле не теряется, а записывается так, как ее показывает форма
+            (FieldType::Text, Data::DateTime(_) | Data::DateTimeIso(_)) => {
+                FieldType::Date.convert(value).and_then(|date| match date {
+                    XlDataType::Date(date) => Some(XlDataType::String(format!(
+                        "{:02}.{:02}.{:04}",
+                        date.day, date.month, date.year
+                    ))),
+                    _ => None,
+                })
+            }
+
+            (FieldType::Date, Data::DateTime(date)) => {
+                XlDate::from_excel_serial(date.as_f64()).map(XlDataType::Date)
+            }
+            (FieldType::Date, Data::Float(serial)) => {
+                XlDate::from_excel_serial(*serial).map(XlDataType::Date)
+            }
+            (FieldType::Date, Data::DateTimeIso(text)) => {
+                XlDate::parse_iso(text).map(XlDataType::Date)
+            }
+            (FieldType::Date, Data::String(text)) => XlDate::parse_dmy(text).map(XlDataType::Date),
+
+            (FieldType::Number, Data::Float(number)) => Some(XlDataType::Float(*number)),
+            (FieldType::Number, Data::Int(number)) => Some(XlDataType::Float(*number as f64)),
+            (FieldType::Money, Data::String(text)) => parse_money(text).map(XlDataType::Float),
+            (FieldType::Number, Data::String(text)) => parse_number(text).map(XlDataType::Float),
+
+            (FieldType::Integer, Data::Int(number)) => Some(XlDataType::Int(*number)),
+            (FieldType::Integer, Data::Float(number)) => float_to_int(*number).map(XlDataType::Int),
+            (FieldType::Integer, Data::String(text)) => text
+                .replace([' ', '\u{a0}'], "")
+                .parse::<i64>()
+                .ok()
+                .map(XlDataType::Int),
+
+            (FieldType::DocNumber, Data::String(text)) => {
+                let text = clean_text(text);
+                is_doc_number(&text).then_some(XlDataType::String(text))
+            }
+            // номер, записанный числом, должен быть целым
+            (FieldType::DocNumber, Data::Float(_) | Data::Int(_)) => {
+                match FieldType::Integer.convert(value) {
+                    Some(XlDataType::Int(number)) if number > 0 => {
+                        Some(XlDataType::String(number.to_string()))
+                    }
+                    _ => None,
+                }
+            }
+
+            _ => None,
+        }
+    }
+
+    // Значение, которое собирается при несоответствии типу. Номер документа нестандартного вида
+    // собирается текстом как есть: пользователь видит его в акте и не должен потерять в отчете
+    pub fn raw_value(&self, value: &Data) -> Option<XlDataType> {
+        match self {
+            FieldType::DocNumber => FieldType::Text.convert(value),
+            _ => None,
+        }
+    }
+
+    // Значение годится полю: ячейка не пустая, без ошибки Excel и приводится к типу поля
+    pub fn fits(&self, value: &Data) -> bool {
+        matches!(self.convert(value), Some(converted) if !matches!(converted, XlDataType::Error(_)))
+    }
+
+    // Значение ячейки соседней с расчетным адресом признается значением поля. Строже, чем fits:
+    // число в окне поиска - скорее сумма, трудозатраты или номер строки, чем дата
+    pub fn is_match(&self, value: &Data) -> bool {
+        match (self, value) {
+            (FieldType::Date, Data::Float(_) | Data::Int(_)) => false,
+            _ => self.fits(value),
+        }
+    }
+}
+
+pub fn is_blank(value: &Data) -> bool {
+    match value {
+        Data::Empty => true,
+        Data::String(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn clean_text(text: &str) -> String {
+    text.trim().replace("\r\n", "")
+}
+
+fn float_to_int(number: f64) -> Option<i64> {
+    // граница диапазона i64, которую f64 представляет точно
+    const I64_BOUND: f64 = 9_223_372_036_854_775_808.;
+    (number.fract() == 0.0 && (-I64_BOUND..I64_BOUND).contains(&number)).then_some(number as i64)
+}
+
+// Номер документа: необязательная подпись со знаком "№" ("№", "Смета №"), затем одно слово из букв, цифр
+// и разделителей "-", "/", ".", "_", в котором есть хотя бы одна цифра.
+// Даты, суммы и фразы ("без номера", "13,797 тыс. руб.") номером не считаются
+fn is_doc_number(text: &str) -> bool {
+    let number = match text.split_once('№') {
+        Some((label, number)) if label.chars().all(|ch| ch.is_alphabetic() || ch == ' ') => {
+            number.trim_start()
+        }
+        _ => text,
+    };
+    !number.is_empty()
+        && number.chars().count() <= 50
+        && number.chars().any(|ch| ch.is_ascii_digit())
+        && number
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || matches!(ch, '-' | '/' | '.' | '_'))
+        && XlDate::parse_dmy(number).is_none()
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.replace([' ', '\u{a0}'], "")
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+}
+
+// Сумма в форме всегда указывается в тысячах рублей, даже если подпись "тыс." не дописана
+fn parse_money(text: &str) -> Option<f64> {
+    parse_number(&text.replace("тыс.", "").replace("руб.", "")).map(|number| number * 1000.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FieldType;
+    use crate::shared::types::{XlDataType, XlDate};
+    use calamine::Data;
+
+    #[test]
+    fn field_type_convert_test() {
+        let money = FieldType::Money.convert(&Data::String(" 13,797  тыс. руб.".to_string()));
+        assert!(matches!(money, Some(XlDataType::Float(x)) if (x - 13797.).abs() < 1e-6));
+        let money = FieldType::Money.convert(&Data::String("13,797".to_string()));
+        assert!(matches!(money, Some(XlDataType::Float(x)) if (x - 13797.).abs() < 1e-6));
+
+        let date = FieldType::Date.convert(&Data::String("01.07.2021".to_string()));
+        assert!(matches!(date, Some(XlDataType::Date(x)) if Some(x) == XlDate::new(2021, 7, 1)));
+
+        let doc_number = FieldType::DocNumber.convert(&Data::Float(15.));
+        assert!(matches!(doc_number, Some(XlDataType::String(x)) if x == "15"));
+
+        assert!(FieldType::Number
+            .convert(&Data::String("много".to_string()))
+            .is_none());
+        assert!(!FieldType::DocNumber.is_match(&Data::String("без номера".to_string())));
+        assert!(!FieldType::Text.is_match(&Data::String("  ".to_string())));
+        assert!(FieldType::Text.is_match(&Data::String("40/26-Д".to_string())));
+
+        let error = Data::Error(calamine::CellErrorType::Ref);
+        assert!(
+            matches!(FieldType::Date.convert(&error), Some(XlDataType::Error(x)) if x == "#REF!")
+        );
+        assert!(!FieldType::Date.is_match(&error));
+
+        // число приводится к дате только в расчетном адресе, при поиске соседней ячейки оно не дата
+        assert!(FieldType::Date.fits(&Data::Float(44408.)));
+        assert!(!FieldType::Date.is_match(&Data::Float(44408.)));
+        assert!(FieldType::Date.is_match(&Data::String("31.07.2021".to_string())));
+    }
+
+    #[test]
+    fn field_type_fits_and_is_match() {
+        let text = |text: &str| Data::String(text.to_string());
+        let datetime = Data::DateTimeIso("2021-07-31T00:00:00".to_string());
+
+        assert!(FieldType::Text.fits(&text("ООО \"Ромашка\"")));
+        assert!(FieldType::Text.fits(&Data::Float(15.)));
+        assert!(matches!(
+            FieldType::Text.convert(&datetime),
+            Some(XlDataType::String(x)) if x == "31.07.2021"
+        ));
+        assert!(FieldType::Text.is_match(&datetime));
+        assert!(!FieldType::Text.fits(&Data::Empty));
+
+        assert!(FieldType::Date.fits(&datetime));
+        assert!(FieldType::Date.is_match(&datetime));
+        assert!(!FieldType::Date.fits(&text("июль")));
+
+        assert!(FieldType::Money.fits(&text("13,797 тыс. руб.")));
+        assert!(FieldType::Money.is_match(&text("13,797")));
+        assert!(!FieldType::Money.fits(&Data::Float(13.797)));
+
+        assert!(FieldType::Number.fits(&Data::Float(12.5)));
+        assert!(FieldType::Number.is_match(&text("1 250,5")));
+        assert!(!FieldType::Number.fits(&text("12 чел.-час")));
+
+        assert!(matches!(
+            FieldType::Integer.convert(&Data::Float(15.)),
+            Some(XlDataType::Int(15))
+        ));
+        assert!(matches!(
+            FieldType::Integer.convert(&text("1 024")),
+            Some(XlDataType::Int(1024))
+        ));
+        assert!(FieldType::Integer.is_match(&Data::Int(7)));
+        assert!(!FieldType::Integer.fits(&Data::Float(15.5)));
+        assert!(!FieldType::Integer.fits(&text("15а")));
+
+        assert!(FieldType::DocNumber.fits(&text("№ 40/26-Д")));
+        assert!(FieldType::DocNumber.fits(&text("02-01-01")));
+        assert!(FieldType::DocNumber.is_match(&Data::Int(15)));
+        assert!(!FieldType::DocNumber.fits(&Data::Float(15.5)));
+        assert!(!FieldType::DocNumber.fits(&text("31.07.2021")));
+        assert!(!FieldType::DocNumber.fits(&text("13,797 тыс. руб.")));
+        assert!(!FieldType::DocNumber.is_match(&text("договор 15 от 01.07.2021")));
+        assert!(!FieldType::DocNumber.is_match(&text("№")));
+
+        // ячейка A20 стандартной формы: номер сметы вместе с подписью
+        assert!(matches!(
+            FieldType::DocNumber.convert(&text("Смета № 02-02-01")),
+            Some(XlDataType::String(x)) if x == "Смета № 02-02-01"
+        ));
+        assert!(!FieldType::DocNumber.fits(&text("Смета № без номера")));
+        assert!(!FieldType::DocNumber.fits(&text("15 № 2")));
+        // номер нестандартного вида не теряется, а собирается текстом
+        assert!(matches!(
+            FieldType::DocNumber.raw_value(&text("б/н от 01.07.2021")),
+            Some(XlDataType::String(x)) if x == "б/н от 01.07.2021"
+        ));
+        assert!(FieldType::Date.raw_value(&text("июль")).is_none());
+    }
+}
@@ -1,8 +1,17 @@
 mod act;
+mod act_store;
 mod books;
+mod explain;
+mod field_type;
 mod sheet;
 mod tags;
+mod totals_names;
+mod validation;
 
 pub use act::{Act, TotalsRow};
-pub use books::ExtractedBooks;
+pub use act_store::{ActStore, PackedAct};
+pub use books::{Book, DiscoveryOptions, ExtractedBooks, SkippedEntry};
+pub use explain::explain;
 pub use sheet::Sheet;
+pub use totals_names::{normalize_totals_names, totals_column_order, TotalsNameDictionary};
+pub use validation::{Issue, Severity};
@@ -1,20 +1,34 @@
 mod verify;
 use super::books::Book;
-use super::tags::{TagAddressMap, TextCmp, TAG_INFO_ARRAY};
+use super::tags::{TagAddressMap, TagID, TAG_INFO_ARRAY};
+use super::validation::ValidationReport;
 use crate::errors::Error;
-use calamine::{DataType, Range, Reader};
+use crate::shared::utils;
+use calamine::{Data, DataType, Dimensions, Range, Reader};
 use std::path::PathBuf;
 
 pub struct Sheet {
     pub path: PathBuf,
     pub sheet_name: String,
-    pub data: Range<DataType>,
+    pub data: Range<Data>,
     pub tag_address_map: TagAddressMap,
     pub range_start: (usize, usize),
+    pub missing_tags: Vec<TagID>,
+    pub misplaced_tags: Vec<(TagID, (usize, usize))>, // обязательные теги, найденные вне установленного порядка
+    pub validation: ValidationReport,
+    pub merged_regions: Vec<Dimensions>, // объединенные области листа в абсолютных координатах
 }
 
 impl<'a> Sheet {
-    pub fn new(
+    pub fn new(workbook: Book, user_entered_sh_name: &'a str) -> Result<Sheet, Error<'a>> {
+        let mut result = Self::new_unverified(workbook, user_entered_sh_name)?;
+        result.validation = result.verify()?;
+        Ok(result)
+    }
+
+    // Чтение листа и поиск тегов без валидации: нужно диагностическому разбору файла,
+    // которому важно показать все найденное даже для акта, не проходящего проверки
+    pub fn new_unverified(
         // разработчики Calamine зачем-то требуют передать &mut в функцию worksheet_range(&mut self, name: &str),
         // из-за этого workbook приходится держать мутабельным, хотя этот код его менять не собирается
         // (это создает ряд проблем, в частности из-за мутабельности workbook приходится чаще клонировать)
@@ -41,14 +55,6 @@ impl<'a> Sheet {
         let xl_sheet = workbook
             .data
             .worksheet_range(&sheet_name)
-            .ok_or({
-                let path_clone = workbook.path.clone();
-                Error::CalamineSheetOfTheBookIsUndetectable {
-                    file_path: path_clone,
-                    sh_name_for_search: user_entered_sh_name,
-                    sh_names: workbook.data.sheet_names().to_owned(),
-                }
-            })?
             .map_err(|error| {
                 let path_clone = workbook.path.clone();
                 Error::CalamineSheetOfTheBookIsUnreadable {
@@ -67,86 +73,158 @@ impl<'a> Sheet {
             }
         })?;
 
+        // шапка формы держится на объединенных ячейках, значение которых хранится только в левой верхней
+        workbook.data.load_merged_regions().map_err(|error| {
+            let path_clone = workbook.path.clone();
+            Error::CalamineSheetOfTheBookIsUnreadable {
+                file_path: path_clone,
+                sh_name: sheet_name.to_owned(),
+                err: error,
+            }
+        })?;
+        let merged_regions = workbook
+            .data
+            .merged_regions_by_sheet(&sheet_name)
+            .into_iter()
+            .map(|(_, _, dimensions)| *dimensions)
+            .collect();
+
         let mut tag_address_map = TagAddressMap::new();
 
-        let mut limited_cell_iterator = xl_sheet.used_cells();
-        let mut found_cell;
+        let mut missing_tags = Vec::new();
+        let mut misplaced_tags = Vec::new();
+        // Адрес последнего найденного обязательного тега: следующий обязательный тег ищется только после него,
+        // что обеспечивает валидацию очередности вхождения тегов (например, "Стройку" мы ожидаем выше "Объекта", не наоборот).
+        // Необязательные теги ищутся по всему листу и на очередность не влияют
+        let mut last_required_adr: Option<(usize, usize)> = None;
 
         for tag_info in TAG_INFO_ARRAY {
-            let mut non_limited_cell_iterator = xl_sheet.used_cells();
-
-            // Для обязательных тегов расходуемый итератор обеспечит валидацию очередности вохождения тегов
-            // (например, "Стройку" мы ожидаем выше "Объекта, не наоборот).
-            // Необязательным тегам расходуемый итератор не подходит, т.к. необязательный тег при отсутсвии израсходует итератор
-            let iterator = if tag_info.is_required {
-                &mut limited_cell_iterator
-            } else {
-                &mut non_limited_cell_iterator
+            let is_tag_cell = |cell: &(usize, usize, &Data)| match cell.2.get_string() {
+                Some(cell_content) => tag_info.is_match(cell_content),
+                None => false,
             };
 
-            found_cell = iterator.find(|cell| match cell.2.get_string() {
-                Some(cell_content) => {
-                    let cell_content = if tag_info.match_case {
-                        cell_content.to_owned()
-                    } else {
-                        cell_content.to_lowercase()
-                    };
-                    let search_content = if tag_info.match_case {
-                        tag_info.id.as_str().to_owned()
-                    } else {
-                        tag_info.id.as_str().to_lowercase()
-                    };
-
-                    match tag_info.look_at {
-                        TextCmp::Whole => cell_content == search_content,
-                        TextCmp::Part => cell_content.contains(&search_content),
-                        TextCmp::StartsWith => cell_content.starts_with(&search_content),
-                        TextCmp::EndsWith => cell_content.ends_with(&search_content),
-                    }
-                }
-                None => false,
+            let found_cell = xl_sheet.used_cells().find(|cell| {
+                let is_in_order = !tag_info.is_required
+                    || last_required_adr.map_or(true, |last_adr| (cell.0, cell.1) > last_adr);
+                is_in_order && is_tag_cell(cell)
             });
 
-            if let Some((row, col, _)) = found_cell {
-                tag_address_map.insert(tag_info.id, (row, col));
+            match found_cell {
+                Some((row, col, _)) => {
+                    tag_address_map.insert(tag_info.id, (row, col));
+                    if tag_info.is_required {
+                        last_required_adr = Some((row, col));
+                    }
+                }
+                None if tag_info.is_required => match xl_sheet.used_cells().find(is_tag_cell) {
+                    Some((row, col, _)) => misplaced_tags.push((tag_info.id, (row, col))),
+                    None => missing_tags.push(tag_info.id),
+                },
+                None => (),
             }
         }
 
-        // Валидация на полноту данных: выше итератор расходующий ячейки и если хоть один поиск провалился, то это преждевременно
-        // потребит все ячейки и извлечение по тегу последней строки в SEARCH_TAGS гарантированно провалится
-        let validation_tag = TAG_INFO_ARRAY
-            .iter()
-            .filter(|search_tag| search_tag.is_required)
-            .last()
-            .ok_or_else(|| Error::InternalLogic {
-                tech_descr: "Массив с тегами для поиска пуст".to_string(),
-                err: None,
-            })?
-            .id;
-
-        tag_address_map
-            .get(&validation_tag)
-            // нужно подменить штатную ошибку на ошибку валидации
-            .map_err(|_| {
-                let path_clone = workbook.path.clone();
-                Error::SheetNotContainAllNecessaryData {
-                    file_path: path_clone,
-                }
-            })?;
-
         let range_start = (sheet_start_coords.0 as usize, sheet_start_coords.1 as usize);
 
-        let result = Sheet {
+        Ok(Sheet {
             path: workbook.path.clone(),
             sheet_name,
             data: xl_sheet,
             tag_address_map,
             range_start,
-        };
+            missing_tags,
+            misplaced_tags,
+            validation: ValidationReport::default(),
+            merged_regions,
+        })
+    }
 
-        verify::check_row_type_alignment(&result)?;
-        verify::check_col_type_alignment(&result)?;
+    // Все проверки листа выполняются полностью, ошибки возвращаются одним списком
+    pub fn verify(&self) -> Result<ValidationReport, Error<'static>> {
+        let mut report = ValidationReport::default();
 
-        Ok(result)
+        verify::check_required_tags(self, &mut report);
+        verify::check_row_type_alignment(self, &mut report);
+        verify::check_col_type_alignment(self, &mut report);
+        verify::check_optional_tags(self, &mut report);
+
+        report.into_result(&self.path)
+    }
+
+    // Адрес ячейки, в которой фактически хранится значение: для ячейки внутри объединенной области
+    // это ее левая верхняя ячейка, для прочих - сама ячейка (координаты внутри диапазона листа)
+    pub fn resolve_merged_address(&self, adr_in_range: (usize, usize)) -> (usize, usize) {
+        let abs_row = adr_in_range.0 + self.range_start.0;
+        let abs_col = adr_in_range.1 + self.range_start.1;
+
+        self.merged_regions
+            .iter()
+            .find(|region| region.contains(abs_row as u32, abs_col as u32))
+            .and_then(|region| {
+                let row = (region.start.0 as usize).checked_sub(self.range_start.0)?;
+                let col = (region.start.1 as usize).checked_sub(self.range_start.1)?;
+                Some((row, col))
+            })
+            .unwrap_or(adr_in_range)
+    }
+
+    // Значение ячейки с учетом объединенных областей
+    pub fn get_value(&self, adr_in_range: (usize, usize)) -> Option<&Data> {
+        self.data.get(self.resolve_merged_address(adr_in_range))
+    }
+
+    // Адрес ячейки в нотации A1 по координатам внутри диапазона листа
+    pub fn get_a1_address(&self, adr_in_range: (usize, usize)) -> Result<String, Error<'static>> {
+        let zero_based_row = adr_in_range.0 + self.range_start.0;
+        let zero_based_col = adr_in_range.1 + self.range_start.1;
+
+        let u16_zero_based_col: u16 =
+            zero_based_col
+                .try_into()
+                .map_err(|err| Error::NumericConversion {
+                    tech_descr: format!(
+                        r#"Не удалась конвертация типа usize с значением "{}" в тип u16."#,
+                        zero_based_col
+                    ),
+                    err: Box::new(err),
+                })?;
+
+        Ok(format!(
+            "{}{}",
+            utils::get_xl_column_letter(u16_zero_based_col),
+            zero_based_row + 1
+        ))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::Sheet;
+    use crate::extract::tags::TagAddressMap;
+    use crate::extract::validation::ValidationReport;
+    use calamine::{Dimensions, Range};
+    use std::path::PathBuf;
+
+    #[test]
+    fn resolve_merged_address_test() {
+        let sheet = Sheet {
+            path: PathBuf::new(),
+            sheet_name: String::new(),
+            data: Range::new((2, 1), (10, 10)),
+            tag_address_map: TagAddressMap::new(),
+            range_start: (2, 1),
+            missing_tags: Vec::new(),
+            misplaced_tags: Vec::new(),
+            validation: ValidationReport::default(),
+            merged_regions: vec![
+                Dimensions::new((4, 3), (5, 7)),
+                Dimensions::new((0, 0), (3, 1)), // начинается выше диапазона листа
+            ],
+        };
+
+        assert_eq!(sheet.resolve_merged_address((3, 4)), (2, 2));
+        assert_eq!(sheet.resolve_merged_address((2, 2)), (2, 2));
+        assert_eq!(sheet.resolve_merged_address((5, 5)), (5, 5));
+        assert_eq!(sheet.resolve_merged_address((1, 0)), (1, 0));
     }
-}
\ No newline at end of file
+}
@@ -1,9 +1,73 @@
 use super::Sheet;
 use crate::errors::Error;
+use crate::extract::act::Act;
 use crate::extract::tags::{Column, Row, TagID, TAG_INFO_ARRAY};
+use crate::extract::validation::{Severity, ValidationReport};
+use crate::shared::lang::tr;
 use crate::shared::utils;
 
-pub fn check_row_type_alignment(sheet: &Sheet) -> Result<(), Error<'static>> {
+pub fn check_required_tags(sheet: &Sheet, report: &mut ValidationReport) {
+    if sheet.missing_tags.is_empty() && sheet.misplaced_tags.is_empty() {
+        return;
+    }
+
+    let mut misplaced_tags = Vec::new();
+    for (tag_id, adr) in sheet.misplaced_tags.iter() {
+        match sheet.get_a1_address(*adr) {
+            Ok(address) => misplaced_tags.push((tag_id.as_str(), address)),
+            Err(err) => report.push(Severity::Error, err.to_string()),
+        }
+    }
+
+    let err = Error::SheetNotContainAllNecessaryData {
+        missing_tags: sheet.missing_tags.iter().map(TagID::as_str).collect(),
+        misplaced_tags,
+        expected_order: TAG_INFO_ARRAY
+            .iter()
+            .filter(|tag_info| tag_info.is_required)
+            .map(|tag_info| tag_info.id.as_str())
+            .collect(),
+    };
+    report.push(Severity::Error, err.to_string());
+}
+
+// Сведения выдаются только о необязательных тегах, без которых остается пустым какое-либо поле шапки.
+// Прочие необязательные теги отсутствуют в большинстве форм намеренно, сведения о них только заслоняли бы
+// в реестре замечаний предупреждения
+pub fn check_optional_tags(sheet: &Sheet, report: &mut ValidationReport) {
+    let missing_optional_tags = TAG_INFO_ARRAY.iter().filter(|tag_info| {
+        !tag_info.is_required && sheet.tag_address_map.get(&tag_info.id).is_err()
+    });
+
+    for tag_info in missing_optional_tags {
+        let dependent_fields = Act::header_fields_depending_on(tag_info.id);
+        if dependent_fields.is_empty() {
+            continue;
+        }
+        let fields_list = dependent_fields
+            .iter()
+            .map(|name| format!("'{name}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        report.push(
+            Severity::Info,
+            tr(
+                format!(
+                    "Необязательное ключевое слово '{}' не найдено, поля шапки останутся пустыми: {fields_list}.",
+                    tag_info.id.as_str()
+                ),
+                format!(
+                    "The optional keyword '{}' was not found, the header fields will be empty: {fields_list}.",
+                    tag_info.id.as_str()
+                ),
+            ),
+        );
+    }
+}
+
+// Обязательные теги, отсутствие которых уже учтено в check_required_tags, при проверке выравнивания пропускаются
+pub fn check_row_type_alignment(sheet: &Sheet, report: &mut ValidationReport) {
     let is_row_algmnt_check = true;
     let mut valid_header_adr: Option<(usize, usize)> = None;
     let mut valid_header_tag_id: Option<TagID> = None;
@@ -15,13 +79,7 @@ pub fn check_row_type_alignment(sheet: &Sheet) -> Result<(), Error<'static>> {
     for tag_info in filterd_tag_infos {
         let tag_adr = match sheet.tag_address_map.get(&tag_info.id) {
             Ok(adr) => adr,
-            Err(err) => {
-                if tag_info.is_required {
-                    return Err(err);
-                } else {
-                    continue;
-                }
-            }
+            Err(_) => continue,
         };
 
         if let Some(group_name) = tag_info.group_by_row {
@@ -35,22 +93,21 @@ pub fn check_row_type_alignment(sheet: &Sheet) -> Result<(), Error<'static>> {
             match group_name {
                 Row::TableHeader => {
                     if valid_header_adr.map(|adr| adr.0) != Some(tag_adr.0) {
-                        return Err(pack_into_error(
+                        let err = pack_into_error(
                             sheet,
                             valid_header_tag_id,
                             tag_info.id,
                             is_row_algmnt_check,
-                        ));
+                        );
+                        report.push(Severity::Error, err.to_string());
                     }
                 }
             }
         }
     }
-
-    Ok(())
 }
 
-pub fn check_col_type_alignment(sheet: &Sheet) -> Result<(), Error<'static>> {
+pub fn check_col_type_alignment(sheet: &Sheet, report: &mut ValidationReport) {
     let is_row_algmnt_check = false;
     let mut valid_initial_adr: Option<(usize, usize)> = None;
     let mut valid_initial_tag_id: Option<TagID> = None;
@@ -64,13 +121,7 @@ pub fn check_col_type_alignment(sheet: &Sheet) -> Result<(), Error<'static>> {
     for tag_info in filterd_tag_infos {
         let tag_adr = match sheet.tag_address_map.get(&tag_info.id) {
             Ok(adr) => adr,
-            Err(err) => {
-                if tag_info.is_required {
-                    return Err(err);
-                } else {
-                    continue;
-                }
-            }
+            Err(_) => continue,
         };
 
         if let Some(group_name) = tag_info.group_by_col {
@@ -83,12 +134,13 @@ pub fn check_col_type_alignment(sheet: &Sheet) -> Result<(), Error<'static>> {
                     }
 
                     if valid_initial_adr.map(|adr| adr.1) != Some(tag_adr.1) {
-                        return Err(pack_into_error(
+                        let err = pack_into_error(
                             sheet,
                             valid_initial_tag_id,
                             tag_info.id,
                             is_row_algmnt_check,
-                        ));
+                        );
+                        report.push(Severity::Error, err.to_string());
                     }
                 }
                 Column::Contract => {
@@ -99,19 +151,18 @@ pub fn check_col_type_alignment(sheet: &Sheet) -> Result<(), Error<'static>> {
                     }
 
                     if valid_contract_adr.map(|adr| adr.1) != Some(tag_adr.1) {
-                        return Err(pack_into_error(
+                        let err = pack_into_error(
                             sheet,
                             valid_contract_tag_id,
                             tag_info.id,
                             is_row_algmnt_check,
-                        ));
+                        );
+                        report.push(Severity::Error, err.to_string());
                     }
                 }
             }
         }
     }
-
-    Ok(())
 }
 
 fn pack_into_error(
@@ -144,7 +195,6 @@ fn pack_into_error(
         fst_tag_index_on_sheet,
         snd_tag_str: second_tag.as_str(),
         snd_tag_index_on_sheet,
-        file_path: sheet.path.clone(),
     }
 }
 
@@ -181,3 +231,52 @@ fn get_xl_column_letter_or_row_idx(
 
     Ok(tag_index_on_sheet)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::check_optional_tags;
+    use crate::extract::act::Act;
+    use crate::extract::sheet::Sheet;
+    use crate::extract::tags::{TagAddressMap, TagID, TAG_INFO_ARRAY};
+    use crate::extract::validation::ValidationReport;
+    use calamine::Range;
+    use std::path::PathBuf;
+
+    #[test]
+    fn check_optional_tags_reports_header_dependencies() {
+        let mut tag_address_map = TagAddressMap::new();
+        for (index, tag_info) in TAG_INFO_ARRAY.iter().enumerate() {
+            if tag_info.id != TagID::ЗтрВсего {
+                tag_address_map.insert(tag_info.id, (index * 2, index));
+            }
+        }
+        let sheet = Sheet {
+            path: PathBuf::new(),
+            sheet_name: String::new(),
+            data: Range::new((0, 0), (40, 20)),
+            tag_address_map,
+            range_start: (0, 0),
+            missing_tags: Vec::new(),
+            misplaced_tags: Vec::new(),
+            validation: ValidationReport::default(),
+            merged_regions: Vec::new(),
+        };
+
+        let mut report = ValidationReport::default();
+        check_optional_tags(&sheet, &mut report);
+        assert_eq!(report.issues().len(), 1);
+        let message = &report.issues()[0].message;
+        assert!(message.contains(TagID::ЗтрВсего.as_str()));
+        assert!(message.contains("Затраты труда, чел.-час"));
+
+        assert_eq!(
+            Act::header_fields_depending_on(TagID::ИтогоПоАкту),
+            vec!["Затраты труда, чел.-час"]
+        );
+        assert_eq!(
+            Act::header_fields_depending_on(TagID::Генподрядчик),
+            vec!["Генподрядчик"]
+        );
+        assert!(Act::header_fields_depending_on(TagID::Стройка).contains(&"Глава"));
+    }
+}
@@ -54,12 +54,10 @@ impl TagID {
     }
 }
 
-// режим сравнения двух текстов: частичное или полное совпадение
+// режим сравнения двух текстов: полное совпадение или совпадение начала
 pub enum TextCmp {
-    Part,
     Whole,
     StartsWith,
-    EndsWith,
 }
 
 pub struct TagInfo {
@@ -69,27 +67,108 @@ pub struct TagInfo {
     pub group_by_col: Option<Column>,
     pub look_at: TextCmp,
     pub match_case: bool,
+    pub max_typos: u8, // допустимое число опечаток (расстояние Левенштейна) после нормализации текста
+}
+
+impl TagInfo {
+    pub fn is_match(&self, cell_content: &str) -> bool {
+        let prepare = |text: &str| {
+            let text = normalize_text(text);
+            if self.match_case {
+                text
+            } else {
+                text.to_lowercase()
+            }
+        };
+        let cell_content = prepare(cell_content);
+        let search_content = prepare(self.id.as_str());
+        let max_typos = self.max_typos as usize;
+
+        // для начала с опечатками сравнивается часть ячейки той же длины, что и тег
+        let search_len = search_content.chars().count();
+
+        match self.look_at {
+            TextCmp::Whole => is_similar(&cell_content, &search_content, max_typos),
+            TextCmp::StartsWith if max_typos == 0 => cell_content.starts_with(&search_content),
+            TextCmp::StartsWith => {
+                let cell_start: String = cell_content.chars().take(search_len).collect();
+                is_similar(&cell_start, &search_content, max_typos)
+            }
+        }
+    }
+}
+
+// Приведение текста ячейки к единому виду: пробелы любого вида (включая неразрывный и переносы строк)
+// схлопываются в один, "ё" заменяется на "е", варианты кавычек и тире - на обычные
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .map(|ch| match ch {
+            'ё' => 'е',
+            'Ё' => 'Е',
+            '«' | '»' | '“' | '”' | '„' | '‟' | '″' => '"',
+            '‘' | '’' | '‚' | '′' => '\'',
+            '‐' | '‑' | '‒' | '–' | '—' | '―' | '−' => '-',
+            _ => ch,
+        })
+        .collect()
+}
+
+fn is_similar(first: &str, second: &str, max_distance: usize) -> bool {
+    if max_distance == 0 {
+        return first == second;
+    }
+
+    let first: Vec<char> = first.chars().collect();
+    let second: Vec<char> = second.chars().collect();
+
+    // разница в длине - нижняя граница расстояния, экономит вычисления на заведомо разных ячейках
+    if first.len().abs_diff(second.len()) > max_distance {
+        return false;
+    }
+
+    levenshtein_distance(&first, &second) <= max_distance
+}
+
+fn levenshtein_distance(first: &[char], second: &[char]) -> usize {
+    let mut prev_row: Vec<usize> = (0..=second.len()).collect();
+    let mut curr_row = vec![0; second.len() + 1];
+
+    for (i, first_ch) in first.iter().enumerate() {
+        curr_row[0] = i + 1;
+        for (j, second_ch) in second.iter().enumerate() {
+            let substitution_cost = if first_ch == second_ch { 0 } else { 1 };
+            curr_row[j + 1] = (prev_row[j] + substitution_cost)
+                .min(prev_row[j + 1] + 1)
+                .min(curr_row[j] + 1);
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    prev_row[second.len()]
 }
 
 // Перечислены в порядке вхождения на листе Excel при чтении ячеек слева направо и сверху вниз  (важно для валидации)
-// При изменении поправить display для типа Error::SheetNotContainAllNecessaryData
+// Опечатки (max_typos) не допускаются тегам, которые короткие или отличаются от соседних текстов одним символом (например, год цен)
 #[rustfmt::skip]
 pub const TAG_INFO_ARRAY: [TagInfo; 15] = [
-    TagInfo { id: TagID::Генподрядчик,                       is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::Субподрядчик,                       is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::Исполнитель,                        is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::Стройка,                            is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::Объект,                             is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::ДоговорПодряда,                     is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Contract), look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::ДопСоглашение,                      is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Contract), look_at: TextCmp::Whole,      match_case: false },
-    TagInfo { id: TagID::НомерДокумента,                     is_required: true,  group_by_row: None,                   group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::ОПриемкеВыполненныхРабот,           is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::НаименованиеРаботИЗатрат,           is_required: true,  group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::СтоимостьВЦенах2001,                is_required: true,  group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::StartsWith, match_case: true },
-    TagInfo { id: TagID::СтоимостьВТекущихЦенах,             is_required: true,  group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::ЗтрВсего,                           is_required: false, group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::StartsWith, match_case: true },
-    TagInfo { id: TagID::ИтогоПоАкту,                        is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true },
-    TagInfo { id: TagID::СтоимостьМатериальныхРесурсовВсего, is_required: true,  group_by_row: None,                   group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true },
+    TagInfo { id: TagID::Генподрядчик,                       is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true,  max_typos: 1 },
+    TagInfo { id: TagID::Субподрядчик,                       is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true,  max_typos: 1 },
+    TagInfo { id: TagID::Исполнитель,                        is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true,  max_typos: 1 },
+    TagInfo { id: TagID::Стройка,                            is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true,  max_typos: 0 },
+    TagInfo { id: TagID::Объект,                             is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true,  max_typos: 0 },
+    TagInfo { id: TagID::ДоговорПодряда,                     is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Contract), look_at: TextCmp::Whole,      match_case: true,  max_typos: 1 },
+    TagInfo { id: TagID::ДопСоглашение,                      is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Contract), look_at: TextCmp::Whole,      match_case: false, max_typos: 1 },
+    TagInfo { id: TagID::НомерДокумента,                     is_required: true,  group_by_row: None,                   group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true,  max_typos: 1 },
+    TagInfo { id: TagID::ОПриемкеВыполненныхРабот,           is_required: true,  group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true,  max_typos: 2 },
+    TagInfo { id: TagID::НаименованиеРаботИЗатрат,           is_required: true,  group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true,  max_typos: 2 },
+    TagInfo { id: TagID::СтоимостьВЦенах2001,                is_required: true,  group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::StartsWith, match_case: true,  max_typos: 0 },
+    TagInfo { id: TagID::СтоимостьВТекущихЦенах,             is_required: true,  group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true,  max_typos: 1 },
+    TagInfo { id: TagID::ЗтрВсего,                           is_required: false, group_by_row: Some(Row::TableHeader), group_by_col: None,                   look_at: TextCmp::StartsWith, match_case: true,  max_typos: 0 },
+    TagInfo { id: TagID::ИтогоПоАкту,                        is_required: false, group_by_row: None,                   group_by_col: Some(Column::Initial),  look_at: TextCmp::Whole,      match_case: true,  max_typos: 0 },
+    TagInfo { id: TagID::СтоимостьМатериальныхРесурсовВсего, is_required: true,  group_by_row: None,                   group_by_col: None,                   look_at: TextCmp::Whole,      match_case: true,  max_typos: 2 },
 ];
 
 // (не удалять)
@@ -133,3 +212,39 @@ impl TagAddressMap {
         self.data.insert(key, data);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_text, TagID, TagInfo, TextCmp};
+
+    #[test]
+    fn normalize_text_test() {
+        assert_eq!(
+            normalize_text(" Наименование\u{a0}работ\r\nи  затрат "),
+            "Наименование работ и затрат"
+        );
+        assert_eq!(normalize_text("«Ёлка» — ёж"), "\"Елка\" - еж");
+    }
+
+    #[test]
+    fn tag_info_is_match_with_typos() {
+        let tag_info = TagInfo {
+            id: TagID::НаименованиеРаботИЗатрат,
+            is_required: true,
+            group_by_row: None,
+            group_by_col: None,
+            look_at: TextCmp::Whole,
+            match_case: true,
+            max_typos: 2,
+        };
+        assert!(tag_info.is_match("Наименование работ\nи затрат "));
+        assert!(tag_info.is_match("Наименованние работ и затрат"));
+        assert!(!tag_info.is_match("Наименование"));
+
+        let tag_info = TagInfo {
+            max_typos: 0,
+            ..tag_info
+        };
+        assert!(!tag_info.is_match("Наименованние работ и затрат"));
+    }
+}
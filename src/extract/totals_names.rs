@@ -0,0 +1,359 @@
+use super::act::{Act, TotalsRow};
+use super::tags::normalize_text;
+use super::validation::Severity;
+use crate::shared::constants::TOTALS_PERCENT_SUFFIX;
+use crate::shared::lang::tr;
+use crate::shared::types::XlDataType;
+use std::collections::HashMap;
+
+// Типовые имена итоговых строк формы в порядке их следования в форме: их написание считается каноническим
+// независимо от того, как строка записана в первом попавшемся акте. Этот же порядок по умолчанию задает порядок столбцов отчета
+const STANDARD_TOTALS_NAMES: [&str; 12] = [
+    "Стоимость материальных ресурсов (всего)",
+    "Эксплуатация машин",
+    "ЗП машинистов",
+    "Основная ЗП рабочих",
+    "Трудозатраты строителей",
+    "Трудозатраты машинистов",
+    "Накладные расходы",
+    "Сметная прибыль",
+    "Всего с НР и СП",
+    "Стоимость давальческих материалов",
+    "Итого за вычетом давальческих материалов",
+    "Итого с К = 1",
+];
+
+// Встроенные синонимы имен итоговых строк: (синоним, каноническое имя).
+// Дополняются и переопределяются ключом "totals_alias" файла настроек
+#[rustfmt::skip]
+const DEFAULT_TOTALS_ALIASES: [(&str, &str); 4] = [
+    ("НР",                                  "Накладные расходы"),
+    ("СП",                                  "Сметная прибыль"),
+    ("Заработная плата машинистов",         "ЗП машинистов"),
+    ("Основная заработная плата рабочих",   "Основная ЗП рабочих"),
+];
+
+// Словарь имен итоговых строк. Имена, отличающиеся лишь пробелами, регистром и знаками препинания,
+// сводятся к одному написанию (типовому, а для прочих - первому встреченному), синонимы - к каноническому имени.
+// Без этого одна и та же строка разных актов превращается в отчете в несколько столбцов
+pub struct TotalsNameDictionary {
+    aliases: HashMap<String, String>,
+    canonical_names: HashMap<String, String>,
+    group_percent: bool, // строки с процентом в конце имени группируются по имени без процента
+}
+
+impl TotalsNameDictionary {
+    pub fn new(extra_aliases: &[(String, String)], group_percent: bool) -> TotalsNameDictionary {
+        let aliases: HashMap<String, String> = DEFAULT_TOTALS_ALIASES
+            .iter()
+            .map(|(alias, name)| (alias.to_string(), name.to_string()))
+            .chain(extra_aliases.iter().cloned())
+            .map(|(alias, name)| (name_key(&alias), clean_name(&name)))
+            .collect();
+
+        // цели синонимов из настроек тоже считаются каноническими именами
+        let canonical_names = STANDARD_TOTALS_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(aliases.values().cloned())
+            .map(|name| (name_key(&name), name))
+            .collect();
+
+        TotalsNameDictionary {
+            aliases,
+            canonical_names,
+            group_percent,
+        }
+    }
+
+    pub fn canonical_name(&mut self, name: &str) -> String {
+        let key = name_key(name);
+        let name = match self.aliases.get(&key) {
+            Some(alias_target) => alias_target.clone(),
+            None => clean_name(name),
+        };
+        self.canonical_names
+            .entry(name_key(&name))
+            .or_insert(name)
+            .clone()
+    }
+}
+
+// Приводит имена итоговых строк акта к каноническим. Строки одного акта, получившие одинаковое имя,
+// объединяются; исходные написания остаются в TotalsRow.original_names, каждое изменение отмечается в замечаниях акта.
+// При группировке по проценту "Производство работ в зимнее время 4%" собирается в строку без процента,
+// а сам процент - в отдельную строку "Производство работ в зимнее время, %". У процента одно значение,
+// оно хранится в текущих ценах; экземпляры строки процента совпадают с экземплярами строки без процента
+pub fn normalize_totals_names(act: &mut Act, dictionary: &mut TotalsNameDictionary) {
+    let mut normalized: Vec<TotalsRow> = Vec::with_capacity(act.data_of_totals.len());
+    // процент каждого экземпляра строки normalized с тем же индексом; None - экземпляр без процента
+    let mut percents: Vec<Vec<Option<f64>>> = Vec::with_capacity(act.data_of_totals.len());
+
+    for totals_row in std::mem::take(&mut act.data_of_totals) {
+        let (name, percent) = match split_percent_suffix(&totals_row.name) {
+            Some((base_name, percent)) if dictionary.group_percent => {
+                (dictionary.canonical_name(&base_name), Some(percent))
+            }
+            _ => (dictionary.canonical_name(&totals_row.name), None),
+        };
+
+        for (original_name, row_number) in totals_row
+            .original_names
+            .iter()
+            .zip(totals_row.row_number.iter())
+            .filter(|(original_name, _)| **original_name != name)
+        {
+            act.validation.push(
+                Severity::Info,
+                tr(
+                    format!("Итоговая строка '{original_name}' (стр. {row_number}) собрана как '{name}'."),
+                    format!("The totals row '{original_name}' (row {row_number}) is collected as '{name}'."),
+                ),
+            );
+        }
+
+        let instance_count = totals_row.row_number.len();
+        let index = merge_totals_row(&mut normalized, TotalsRow { name, ..totals_row });
+        if index == percents.len() {
+            percents.push(Vec::new());
+        }
+        percents[index].extend(std::iter::repeat(percent).take(instance_count));
+    }
+
+    // строка с процентом идет следом за своей строкой, чтобы и в отчете оказаться рядом с ней
+    act.data_of_totals = Vec::with_capacity(normalized.len());
+    for (totals_row, row_percents) in normalized.into_iter().zip(percents) {
+        let percent_row = row_percents.iter().any(Option::is_some).then(|| TotalsRow {
+            name: format!("{}{TOTALS_PERCENT_SUFFIX}", totals_row.name),
+            base_price: vec![None; row_percents.len()],
+            curr_price: row_percents
+                .iter()
+                .map(|percent| percent.map(XlDataType::Float))
+                .collect(),
+            row_number: totals_row.row_number.clone(),
+            original_names: totals_row.original_names.clone(),
+            is_percent: true,
+        });
+        act.data_of_totals.push(totals_row);
+        act.data_of_totals.extend(percent_row);
+    }
+}
+
+// Канонический порядок столбцов итогов: список из настроек, а если он не задан - типовые имена формы.
+// Имена списка проходят через словарь, чтобы совпасть с именами, к которым приведены строки актов
+pub fn totals_column_order(
+    configured: &[String],
+    dictionary: &mut TotalsNameDictionary,
+) -> Vec<String> {
+    let names: Vec<&str> = if configured.is_empty() {
+        STANDARD_TOTALS_NAMES.to_vec()
+    } else {
+        configured.iter().map(String::as_str).collect()
+    };
+
+    let mut order: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let name = dictionary.canonical_name(name);
+        if !order.contains(&name) {
+            order.push(name);
+        }
+    }
+    order
+}
+
+// Индекс строки, в которую попали экземпляры totals_row
+fn merge_totals_row(totals: &mut Vec<TotalsRow>, totals_row: TotalsRow) -> usize {
+    match totals.iter().position(|row| row.name == totals_row.name) {
+        Some(index) => {
+            let row = &mut totals[index];
+            row.base_price.extend(totals_row.base_price);
+            row.curr_price.extend(totals_row.curr_price);
+            row.row_number.extend(totals_row.row_number);
+            row.original_names.extend(totals_row.original_names);
+            index
+        }
+        None => {
+            totals.push(totals_row);
+            totals.len() - 1
+        }
+    }
+}
+
+// Отделяет процент в конце имени: "Производство работ в зимнее время 2,8%" -> ("Производство работ в зимнее время", 2.8)
+fn split_percent_suffix(name: &str) -> Option<(String, f64)> {
+    let without_sign = name.trim().strip_suffix('%')?.trim_end();
+    let number_start = without_sign
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| ch.is_ascii_digit() || *ch == ',' || *ch == '.')
+        .last()?
+        .0;
+    let percent = without_sign[number_start..]
+        .replace(',', ".")
+        .parse()
+        .ok()?;
+    let base_name = without_sign[..number_start].trim_end_matches([' ', '-', ',']);
+
+    (!base_name.is_empty()).then(|| (base_name.to_string(), percent))
+}
+
+fn clean_name(name: &str) -> String {
+    normalize_text(name)
+        .trim_end_matches([':', ';', ',', '.'])
+        .trim()
+        .to_string()
+}
+
+// Ключ сравнения имен: без учета регистра, знаков препинания и количества пробелов
+fn name_key(name: &str) -> String {
+    normalize_text(name)
+        .to_lowercase()
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() { ch } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        normalize_totals_names, split_percent_suffix, totals_column_order, TotalsNameDictionary,
+    };
+    use crate::extract::act::{Act, TotalsRow};
+    use crate::settings::Settings;
+    use crate::shared::types::XlDataType;
+
+    #[test]
+    fn totals_name_dictionary_test() {
+        let extra_aliases = vec![(
+            "Зимнее удорожание".to_string(),
+            "Производство работ в зимнее время".to_string(),
+        )];
+        let mut dictionary = TotalsNameDictionary::new(&extra_aliases, false);
+
+        assert_eq!(
+            dictionary.canonical_name("Накладные  расходы "),
+            "Накладные расходы"
+        );
+        assert_eq!(
+            dictionary.canonical_name("накладные расходы:"),
+            "Накладные расходы"
+        );
+        assert_eq!(dictionary.canonical_name("НР"), "Накладные расходы");
+        assert_eq!(dictionary.canonical_name("Итого с К=1"), "Итого с К = 1");
+        assert_eq!(
+            dictionary.canonical_name("Прочие  затраты"),
+            "Прочие затраты"
+        );
+        assert_eq!(
+            dictionary.canonical_name("ПРОЧИЕ ЗАТРАТЫ"),
+            "Прочие затраты"
+        );
+        assert_eq!(
+            dictionary.canonical_name("зимнее удорожание"),
+            "Производство работ в зимнее время"
+        );
+    }
+
+    #[test]
+    fn totals_column_order_test() {
+        let mut dictionary = TotalsNameDictionary::new(&[], false);
+        let configured = vec![
+            "НР".to_string(),
+            "Сметная  прибыль".to_string(),
+            "накладные расходы".to_string(),
+        ];
+        assert_eq!(
+            totals_column_order(&configured, &mut dictionary),
+            vec!["Накладные расходы", "Сметная прибыль"]
+        );
+        assert_eq!(totals_column_order(&[], &mut dictionary).len(), 12);
+    }
+
+    #[test]
+    fn split_percent_suffix_test() {
+        assert_eq!(
+            split_percent_suffix("Производство работ в зимнее время 2,8%"),
+            Some(("Производство работ в зимнее время".to_string(), 2.8))
+        );
+        assert_eq!(
+            split_percent_suffix("Производство работ в зимнее время - 4 %"),
+            Some(("Производство работ в зимнее время".to_string(), 4.))
+        );
+        assert_eq!(split_percent_suffix("Накладные расходы"), None);
+        assert_eq!(split_percent_suffix("4%"), None);
+    }
+
+    #[test]
+    fn normalize_percent_totals_test() {
+        let totals_row = |name: &str, row_number: usize| TotalsRow {
+            name: name.to_string(),
+            base_price: vec![Some(XlDataType::Float(1.))],
+            curr_price: vec![Some(XlDataType::Float(8.))],
+            row_number: vec![row_number],
+            original_names: vec![name.to_string()],
+            is_percent: false,
+        };
+        let mut act = Act::for_test("акт.xlsm", "Лист1");
+        act.data_of_totals = vec![
+            totals_row("Производство работ в зимнее время", 40),
+            totals_row("Производство работ в зимнее время 2,8%", 45),
+        ];
+        let mut dictionary = TotalsNameDictionary::new(&[], true);
+        normalize_totals_names(&mut act, &mut dictionary);
+
+        assert_eq!(act.data_of_totals.len(), 2);
+        let percent_row = &act.data_of_totals[1];
+        assert_eq!(percent_row.name, "Производство работ в зимнее время, %");
+        assert_eq!(percent_row.row_number, vec![40, 45]);
+        assert!(percent_row.base_price.iter().all(Option::is_none));
+        assert!(percent_row.curr_price[0].is_none());
+        assert!(matches!(percent_row.curr_price[1], Some(XlDataType::Float(x)) if x == 2.8));
+    }
+
+    #[test]
+    fn totals_alias_setting() {
+        let settings = Settings::from_text(
+            "totals_alias = НР => Накладные расходы\ntotals_alias = СП=>Сметная прибыль",
+        )
+        .unwrap();
+        assert_eq!(
+            settings.totals_aliases,
+            vec![
+                ("НР".to_string(), "Накладные расходы".to_string()),
+                ("СП".to_string(), "Сметная прибыль".to_string()),
+            ]
+        );
+        assert!(Settings::from_text("totals_alias = НР").is_err());
+    }
+
+    #[test]
+    fn group_percent_totals_setting() {
+        assert!(!Settings::from_text("").unwrap().group_percent_totals);
+        assert!(
+            Settings::from_text("group_percent_totals = да")
+                .unwrap()
+                .group_percent_totals
+        );
+        assert!(
+            !Settings::from_text("group_percent_totals = no")
+                .unwrap()
+                .group_percent_totals
+        );
+        assert!(Settings::from_text("group_percent_totals = иногда").is_err());
+    }
+
+    #[test]
+    fn totals_order_setting() {
+        let settings =
+            Settings::from_text("totals_order = Накладные расходы\ntotals_order = Сметная прибыль")
+                .unwrap();
+        assert_eq!(
+            settings.totals_order,
+            vec!["Накладные расходы", "Сметная прибыль"]
+        );
+        assert!(Settings::from_text("totals_order =").is_err());
+    }
+}
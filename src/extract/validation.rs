@@ -0,0 +1,85 @@
+use crate::errors::Error;
+use crate::shared::lang::tr;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => tr("сведения", "info"),
+            Severity::Warning => tr("предупреждение", "warning"),
+            Severity::Error => tr("ошибка", "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+// Замечания по листу акта. Проверки не останавливаются на первой проблеме, а копят все,
+// чтобы пользователь мог исправить файл за один раз. Ошибки делают акт непригодным к сбору,
+// предупреждения и сведения сопровождают собранный акт до отчета.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity,
+            message: message.into(),
+        });
+    }
+
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn into_result(self, file_path: &Path) -> Result<ValidationReport, Error<'static>> {
+        if self.has_errors() {
+            return Err(Error::SheetValidation {
+                file_path: file_path.to_path_buf(),
+                issues: self.issues,
+            });
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Severity, ValidationReport};
+    use std::path::Path;
+
+    #[test]
+    fn validation_report_into_result() {
+        let mut report = ValidationReport::default();
+        report.push(Severity::Info, "сведения");
+        report.push(Severity::Warning, "предупреждение");
+        assert!(!report.has_errors());
+
+        let report = report.into_result(Path::new("акт.xlsm")).unwrap();
+        assert_eq!(report.issues().len(), 2);
+
+        let mut report = report;
+        report.push(Severity::Error, "первая ошибка");
+        report.push(Severity::Error, "вторая ошибка");
+        assert!(report.into_result(Path::new("акт.xlsm")).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use super::output::{
+    table_file_path, value_to_text, ReportColumn, ReportOutput, ReportTable, ReportValue,
+};
+use crate::errors::Error;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Метка порядка байтов: без нее Excel открывает UTF-8 как однобайтовую кодировку и портит кириллицу
+const UTF8_BOM: &str = "\u{FEFF}";
+
+// Вывод отчета в CSV (RFC 4180): таблица - файл, значения через запятую, строки через CRLF.
+// Оформление не переносится, числа пишутся с точкой, даты - в виде "гггг-мм-дд", ссылки - путем
+pub struct CsvOutput {
+    filepath: PathBuf,
+    files: HashMap<ReportTable, TableFile>,
+}
+
+struct TableFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl CsvOutput {
+    pub fn new(filepath: &Path) -> CsvOutput {
+        CsvOutput {
+            filepath: filepath.to_path_buf(),
+            files: HashMap::new(),
+        }
+    }
+
+    fn write_line<'b>(
+        &mut self,
+        table: ReportTable,
+        fields: impl Iterator<Item = &'b str>,
+    ) -> Result<(), Error<'static>> {
+        let Some(file) = self.files.get_mut(&table) else {
+            return Ok(());
+        };
+        let line = fields.map(escape_field).collect::<Vec<_>>().join(",");
+        write!(file.writer, "{line}\r\n").map_err(|err| Error::ReportFileWrite {
+            file_path: file.path.clone(),
+            err,
+        })
+    }
+}
+
+impl ReportOutput for CsvOutput {
+    fn begin(&mut self, table: ReportTable) -> Result<(), Error<'static>> {
+        let path = table_file_path(&self.filepath, table);
+        let mut writer =
+            File::create(&path)
+                .map(BufWriter::new)
+                .map_err(|err| Error::ReportFileWrite {
+                    file_path: path.clone(),
+                    err,
+                })?;
+        writer
+            .write_all(UTF8_BOM.as_bytes())
+            .map_err(|err| Error::ReportFileWrite {
+                file_path: path.clone(),
+                err,
+            })?;
+        self.files.insert(table, TableFile { path, writer });
+        Ok(())
+    }
+
+    fn write_header_row(
+        &mut self,
+        table: ReportTable,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>> {
+        self.write_line(table, columns.iter().map(|column| column.title.as_str()))
+    }
+
+    fn write_act_row(
+        &mut self,
+        table: ReportTable,
+        row: &[Option<ReportValue>],
+    ) -> Result<(), Error<'static>> {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| value.as_ref().map(value_to_text).unwrap_or_default())
+            .collect();
+        self.write_line(table, fields.iter().map(String::as_str))
+    }
+
+    fn finish(&mut self) -> Result<(), Error<'static>> {
+        for (_, mut file) in self.files.drain() {
+            file.writer.flush().map_err(|err| Error::ReportFileWrite {
+                file_path: file.path.clone(),
+                err,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+// Поле заключается в кавычки, если содержит разделитель, кавычки или перенос строки; кавычки удваиваются
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape_field;
+
+    #[test]
+    fn csv_escape_field() {
+        assert_eq!(escape_field("Накладные расходы"), "Накладные расходы");
+        assert_eq!(
+            escape_field("Глава 1, «Подготовка»"),
+            "\"Глава 1, «Подготовка»\""
+        );
+        assert_eq!(escape_field("ООО \"Ромашка\""), "\"ООО \"\"Ромашка\"\"\"");
+        assert_eq!(escape_field("строка 1\nстрока 2"), "\"строка 1\nстрока 2\"");
+    }
+}
@@ -0,0 +1,211 @@
+use super::output::{
+    table_file_path, value_to_text, ReportColumn, ReportOutput, ReportTable, ReportValue,
+};
+use crate::errors::Error;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Вывод отчета в JSON: таблица - файл вида {"table": имя, "columns": [заголовки], "rows": [[значения], ...]}.
+// Строки - массивы, а не объекты: у столбцов итогов с несколькими экземплярами одинаковые заголовки.
+// Ссылки пишутся объектом {"url", "text"}, ошибки Excel - объектом {"error"}, даты - строкой "гггг-мм-дд"
+pub struct JsonOutput {
+    filepath: PathBuf,
+    files: HashMap<ReportTable, TableFile>,
+}
+
+struct TableFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    size_in_row: usize,
+}
+
+impl TableFile {
+    fn write(&mut self, text: &str) -> Result<(), Error<'static>> {
+        self.writer
+            .write_all(text.as_bytes())
+            .map_err(|err| Error::ReportFileWrite {
+                file_path: self.path.clone(),
+                err,
+            })
+    }
+}
+
+impl JsonOutput {
+    pub fn new(filepath: &Path) -> JsonOutput {
+        JsonOutput {
+            filepath: filepath.to_path_buf(),
+            files: HashMap::new(),
+        }
+    }
+}
+
+impl ReportOutput for JsonOutput {
+    fn begin(&mut self, table: ReportTable) -> Result<(), Error<'static>> {
+        let path = table_file_path(&self.filepath, table);
+        let writer =
+            File::create(&path)
+                .map(BufWriter::new)
+                .map_err(|err| Error::ReportFileWrite {
+                    file_path: path.clone(),
+                    err,
+                })?;
+        let mut file = TableFile {
+            path,
+            writer,
+            size_in_row: 0,
+        };
+        file.write(&format!("{{\"table\": {}", json_string(table.name())))?;
+        self.files.insert(table, file);
+        Ok(())
+    }
+
+    fn write_header_row(
+        &mut self,
+        table: ReportTable,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>> {
+        let Some(file) = self.files.get_mut(&table) else {
+            return Ok(());
+        };
+        let titles = columns
+            .iter()
+            .map(|column| json_string(&column.title))
+            .collect::<Vec<_>>()
+            .join(", ");
+        file.write(&format!(",\n\"columns\": [{titles}],\n\"rows\": ["))
+    }
+
+    fn write_act_row(
+        &mut self,
+        table: ReportTable,
+        row: &[Option<ReportValue>],
+    ) -> Result<(), Error<'static>> {
+        let Some(file) = self.files.get_mut(&table) else {
+            return Ok(());
+        };
+        let values = row
+            .iter()
+            .map(|value| value.as_ref().map_or("null".to_string(), json_value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let separator = if file.size_in_row == 0 { "" } else { "," };
+        file.size_in_row += 1;
+        file.write(&format!("{separator}\n[{values}]"))
+    }
+
+    fn finish(&mut self) -> Result<(), Error<'static>> {
+        for (_, mut file) in self.files.drain() {
+            file.write("\n]}\n")?;
+            file.writer.flush().map_err(|err| Error::ReportFileWrite {
+                file_path: file.path.clone(),
+                err,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn json_value(value: &ReportValue) -> String {
+    match value {
+        ReportValue::Number(number) if number.is_finite() => number.to_string(),
+        ReportValue::Number(_) => "null".to_string(),
+        ReportValue::Integer(number) => number.to_string(),
+        ReportValue::Bool(value) => value.to_string(),
+        ReportValue::Link { target, text } => format!(
+            "{{\"url\": {}, \"text\": {}}}",
+            json_string(target),
+            json_string(text)
+        ),
+        ReportValue::Error(text) => format!("{{\"error\": {}}}", json_string(text)),
+        ReportValue::Text(_) | ReportValue::Date(_) => json_string(&value_to_text(value)),
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if u32::from(ch) < 0x20 => escaped.push_str(&format!("\\u{:04x}", u32::from(ch))),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_string, JsonOutput};
+    use crate::load::output::{ReportColumn, ReportOutput, ReportTable, ReportValue};
+
+    #[test]
+    fn json_escape_string() {
+        assert_eq!(json_string("Накладные расходы"), "\"Накладные расходы\"");
+        assert_eq!(json_string("ООО \"Ромашка\""), "\"ООО \\\"Ромашка\\\"\"");
+        assert_eq!(json_string(r"C:\Акты"), r#""C:\\Акты""#);
+        assert_eq!(
+            json_string("строка 1\r\nстрока 2\tконец"),
+            "\"строка 1\\r\\nстрока 2\\tконец\""
+        );
+        assert_eq!(json_string("\u{1}\u{1f}"), "\"\\u0001\\u001f\"");
+    }
+
+    #[test]
+    fn json_output_rows_separated_by_commas() {
+        let path = std::env::temp_dir().join(format!("ks2_etl_json_{}.json", std::process::id()));
+        let mut output = JsonOutput::new(&path);
+        output.begin(ReportTable::Acts).unwrap();
+        let columns = [
+            ReportColumn::new("Акт №", 10.),
+            ReportColumn::new("Сумма", 10.),
+        ];
+        output
+            .write_header_row(ReportTable::Acts, &columns)
+            .unwrap();
+        output
+            .write_act_row(
+                ReportTable::Acts,
+                &[Some(ReportValue::Text("1".to_string())), None],
+            )
+            .unwrap();
+        output
+            .write_act_row(
+                ReportTable::Acts,
+                &[
+                    Some(ReportValue::Text("2".to_string())),
+                    Some(ReportValue::Number(20.5)),
+                ],
+            )
+            .unwrap();
+        output.finish().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            text,
+            format!(
+                "{{\"table\": {},\n\"columns\": [\"Акт №\", \"Сумма\"],\n\"rows\": [\n[\"1\", null],\n[\"2\", 20.5]\n]}}\n",
+                json_string(ReportTable::Acts.name())
+            )
+        );
+
+        // таблица без строк остается корректным JSON
+        let mut output = JsonOutput::new(&path);
+        output.begin(ReportTable::Acts).unwrap();
+        output
+            .write_header_row(ReportTable::Acts, &columns)
+            .unwrap();
+        output.finish().unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(text.ends_with("\"rows\": [\n]}\n"));
+    }
+}
@@ -0,0 +1,244 @@
+use std::env;
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR};
+
+// Адреса гиперссылок отчета на файлы актов и их папки. По умолчанию ссылка - абсолютный URI "file:///".
+// Относительные ссылки (для случая, когда архив актов и отчет переносятся вместе) строятся от папки отчета
+// (настройка "relative_links") или от заданного корня, в котором будет лежать отчет (настройка "link_root").
+// В абсолютных ссылках начало пути можно заменить (настройка "link_map"), например подключенный диск - сетевой папкой
+#[derive(Debug, Clone, Default)]
+pub struct ReportLinks {
+    base_dir: Option<PathBuf>,         // задана, если ссылки относительные
+    prefix_map: Vec<(String, String)>, // (начало пути, замена) с разделителями '/'
+}
+
+impl ReportLinks {
+    pub fn new(
+        report_path: &Path,
+        relative: bool,
+        root: Option<&Path>,
+        prefix_map: &[(String, String)],
+    ) -> ReportLinks {
+        let base_dir = match root {
+            Some(root) => Some(absolute_path(root)),
+            None if relative => absolute_path(report_path).parent().map(Path::to_path_buf),
+            None => None,
+        };
+        let prefix_map = prefix_map
+            .iter()
+            .map(|(from, to)| (uri_prefix(from), uri_prefix(to)))
+            .collect();
+        ReportLinks {
+            base_dir,
+            prefix_map,
+        }
+    }
+
+    pub fn target(&self, path: &Path) -> String {
+        let path = absolute_path(path);
+        // на другом диске относительного пути нет, остается абсолютная ссылка
+        match self
+            .base_dir
+            .as_ref()
+            .and_then(|base_dir| relative_path(&path, base_dir))
+        {
+            Some(relative) => escape_uri_chars(&to_uri_separators(&relative, MAIN_SEPARATOR)),
+            None => file_uri(&self.map_prefix(to_uri_separators(&path, MAIN_SEPARATOR))),
+        }
+    }
+
+    // Первая подходящая замена начала пути; сравнение без учета регистра и только по целым именам папок
+    fn map_prefix(&self, path: String) -> String {
+        for (from, to) in &self.prefix_map {
+            let Some(head) = path.get(..from.len()) else {
+                continue;
+            };
+            let tail = &path[from.len()..];
+            if head.to_lowercase() == from.to_lowercase()
+                && (tail.is_empty() || tail.starts_with('/'))
+            {
+                return format!("{to}{tail}");
+            }
+        }
+        path
+    }
+}
+
+// Начало пути из настроек: разделители '/', без завершающего разделителя
+fn uri_prefix(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+// Абсолютный путь без обращения к файловой системе: относительный достраивается от текущей папки
+fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn to_uri_separators(path: &Path, separator: char) -> String {
+    path.to_string_lossy().replace(separator, "/")
+}
+
+// URI "file:" для пути с разделителями '/': "C:/акты" -> "file:///C:/акты", "/srv/акты" -> "file:///srv/акты",
+// сетевой путь "//server/share" -> "file://server/share"
+fn file_uri(path: &str) -> String {
+    let path = escape_uri_chars(path);
+    if path.starts_with("//") {
+        format!("file:{path}")
+    } else if path.starts_with('/') {
+        format!("file://{path}")
+    } else {
+        format!("file:///{path}")
+    }
+}
+
+// '#' Excel считает началом адреса внутри книги, '%' - началом кода символа. Остальное, включая кириллицу,
+// не кодируется: Excel понимает пути в Юникоде, а адрес в формуле HYPERLINK ограничен 255 символами
+fn escape_uri_chars(path: &str) -> String {
+    path.replace('%', "%25").replace('#', "%23")
+}
+
+// Путь path относительно папки base; None, если у них разные корни (диски, сетевые ресурсы).
+// В Windows имена папок сравниваются без учета регистра, как их сравнивает файловая система
+fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    relative_path_with_case(path, base, cfg!(windows))
+}
+
+fn relative_path_with_case(path: &Path, base: &Path, ignore_case: bool) -> Option<PathBuf> {
+    let same_component = |left: &Component, right: &Component| {
+        if ignore_case {
+            left.as_os_str().to_string_lossy().to_lowercase()
+                == right.as_os_str().to_string_lossy().to_lowercase()
+        } else {
+            left == right
+        }
+    };
+    let path_components: Vec<Component> = path.components().collect();
+    let base_components: Vec<Component> = base.components().collect();
+    match (path_components.first(), base_components.first()) {
+        (Some(left), Some(right)) if same_component(left, right) => (),
+        (None, None) => (),
+        _ => return None,
+    }
+
+    let common_len = path_components
+        .iter()
+        .zip(base_components.iter())
+        .take_while(|(left, right)| same_component(left, right))
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common_len..base_components.len() {
+        relative.push("..");
+    }
+    for component in &path_components[common_len..] {
+        relative.push(component);
+    }
+    Some(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_uri, relative_path, relative_path_with_case, to_uri_separators, ReportLinks};
+    use crate::settings::Settings;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn report_links_test() {
+        assert_eq!(
+            file_uri(&to_uri_separators(Path::new(r"C:\акты\акт 1.xlsm"), '\\')),
+            "file:///C:/акты/акт 1.xlsm"
+        );
+        assert_eq!(
+            file_uri(&to_uri_separators(
+                Path::new(r"\\server\share\акт.xlsm"),
+                '\\'
+            )),
+            "file://server/share/акт.xlsm"
+        );
+        assert_eq!(
+            file_uri("/srv/акты/акт #2 (100%).xlsm"),
+            "file:///srv/акты/акт %232 (100%25).xlsm"
+        );
+
+        // обычный путь архива целиком на кириллице не раздувается кодированием и умещается в формулу
+        let long_path = r"D:\Объекты\ЖК Северный\Акты КС-2\2023\07 Июль\Субподрядчик ООО Монтажник\Акт КС-2 № 15 от 31.07.2023 по смете 02-02-01.xlsm";
+        let target = file_uri(&to_uri_separators(Path::new(long_path), '\\'));
+        assert_eq!(target, format!("file:///{}", long_path.replace('\\', "/")));
+        assert!(target.chars().count() <= 255);
+
+        assert_eq!(
+            relative_path(
+                Path::new("/srv/архив/2023/акт.xlsm"),
+                Path::new("/srv/архив")
+            ),
+            Some(PathBuf::from("2023/акт.xlsm"))
+        );
+        assert_eq!(
+            relative_path(
+                Path::new("/srv/архив/акт.xlsm"),
+                Path::new("/srv/отчеты/май")
+            ),
+            Some(PathBuf::from("../../архив/акт.xlsm"))
+        );
+        // регистр имен папок различается только там, где его различает файловая система
+        assert_eq!(
+            relative_path_with_case(
+                Path::new("/SRV/Архив/2023/акт.xlsm"),
+                Path::new("/srv/архив"),
+                true
+            ),
+            Some(PathBuf::from("2023/акт.xlsm"))
+        );
+        assert_eq!(
+            relative_path_with_case(
+                Path::new("/SRV/Архив/2023/акт.xlsm"),
+                Path::new("/srv/архив"),
+                false
+            ),
+            Some(PathBuf::from("../../SRV/Архив/2023/акт.xlsm"))
+        );
+
+        let prefix_map = vec![(r"Z:\Архив\".to_string(), r"\\fs01\Архив".to_string())];
+        let links = ReportLinks::new(Path::new("отчет.xlsx"), false, None, &prefix_map);
+        assert_eq!(
+            links.map_prefix("z:/архив/2023/акт.xlsm".to_string()),
+            "//fs01/Архив/2023/акт.xlsm"
+        );
+        assert_eq!(
+            links.map_prefix("Z:/Архив2/акт.xlsm".to_string()),
+            "Z:/Архив2/акт.xlsm"
+        );
+    }
+
+    #[test]
+    fn relative_links_setting() {
+        assert!(!Settings::from_text("").unwrap().relative_links);
+        assert!(
+            Settings::from_text("relative_links = yes")
+                .unwrap()
+                .relative_links
+        );
+        assert!(Settings::from_text("relative_links = рядом").is_err());
+    }
+
+    #[test]
+    fn link_root_and_link_map_settings() {
+        let settings = Settings::from_text(concat!(
+            r"link_root = D:\Архив",
+            "\n",
+            r"link_map = Z:\ => \\fs01\share"
+        ))
+        .unwrap();
+        assert_eq!(settings.link_root, Some(PathBuf::from(r"D:\Архив")));
+        assert_eq!(
+            settings.link_maps,
+            vec![(r"Z:\".to_string(), r"\\fs01\share".to_string())]
+        );
+        assert!(Settings::from_text("link_root =").is_err());
+        assert!(Settings::from_text("link_map = Z:").is_err());
+    }
+}
@@ -0,0 +1,1142 @@
+use crate::errors::Error;
+use crate::extract::{Act, Severity, SkippedEntry, TotalsRow};
+use crate::shared::constants::TOTALS_PERCENT_SUFFIX;
+use crate::shared::lang::tr;
+use crate::shared::types::XlDataType;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+mod csv;
+mod json;
+mod links;
+mod output;
+mod sheet_output;
+mod sink;
+mod sqlite;
+mod xlsx;
+pub use links::ReportLinks;
+pub use output::{OutputFormat, ReportOutput};
+use output::{ReportColumn, ReportTable, ReportValue, Subtotal};
+
+#[derive(Debug)]
+pub struct ExtractionConfig {
+    pub rename: Option<&'static str>,
+    pub moving: Moving,
+    pub expected_columns: u16,
+    pub source: Source,
+}
+#[derive(Debug)]
+pub struct TotalsRowInfo {
+    pub row_name: String,
+    pub row_instance_count: u16,
+    pub is_percent: bool, // процент надбавки: один столбец в текущих ценах, без итога
+}
+#[derive(Debug, Clone, PartialEq)]
+
+pub enum Moving {
+    No,
+    Yes,
+    Del,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Matches {
+    Exact,
+    Contains,
+}
+
+// Четыре вида данных на выходе: 1. в готовом виде в шапке, 2. в готов виде в итогах акта (2 варианта), 3.вычисляемые
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    InTableHeader(&'static str),
+    AtCurrPrices(String, Matches),
+    AtBasePrices(String, Matches),
+    Calculate(&'static str),
+}
+
+#[derive(Debug)]
+pub struct ExcelDataSet {
+    vector: Vec<ExtractionConfig>,
+    number_of_columns: u16,
+}
+impl ExcelDataSet {
+    pub fn new(vector: Vec<ExtractionConfig>) -> ExcelDataSet {
+        let number_of_columns = Self::count_col(&vector);
+
+        ExcelDataSet {
+            vector,
+            number_of_columns,
+        }
+    }
+    pub fn get_number_of_columns(&self) -> u16 {
+        self.number_of_columns
+    }
+
+    pub fn get_index_and_address_by_columns(
+        &self,
+        kind: &str,
+        name: &str,
+        matches: Matches,
+    ) -> Option<(usize, u16)> {
+        let src = match kind {
+            "base" => Source::AtBasePrices("".to_string(), matches.clone()),
+            "curr" => Source::AtCurrPrices("".to_string(), matches.clone()),
+            "calc" => Source::Calculate(""),
+            "header" => Source::InTableHeader(""),
+            _ => panic!(),
+        };
+
+        let mut counter = 0;
+        let mut index = 0;
+
+        for extraction_config in self.vector.iter() {
+            if extraction_config.moving == Moving::Del {
+                continue;
+            }
+
+            match extraction_config {
+                ExtractionConfig {
+                    source: Source::Calculate(text) | Source::InTableHeader(text),
+                    ..
+                } if variant_eq(&extraction_config.source, &src) && &name == text => {
+                    return Some((index, counter));
+                }
+                ExtractionConfig {
+                    source: Source::AtBasePrices(text, m) | Source::AtCurrPrices(text, m),
+                    ..
+                } if variant_eq(&extraction_config.source, &src)
+                    && variant_eq(m, &matches)
+                    && m == &Matches::Exact
+                    && name == text =>
+                {
+                    return Some((index, counter));
+                }
+                ExtractionConfig {
+                    source: Source::AtBasePrices(text, m) | Source::AtCurrPrices(text, m),
+                    ..
+                } if variant_eq(&extraction_config.source, &src)
+                    && variant_eq(m, &matches)
+                    && m == &Matches::Contains
+                    && name.contains(text) =>
+                {
+                    return Some((index, counter));
+                }
+                ExtractionConfig { moving: mov, .. } => {
+                    if mov == &Moving::No || mov == &Moving::Yes {
+                        index += 1;
+                        counter += extraction_config.expected_columns;
+                    }
+                }
+            };
+        }
+        None
+    }
+
+    // Столбцы итоговых строк с точным совпадением имени: (имя, адрес в наборе, количество столбцов).
+    // Адрес считается так же, как в get_index_and_address_by_columns
+    pub fn exact_price_columns<'b>(
+        &'b self,
+        kind: &'b str,
+    ) -> impl Iterator<Item = (&'b str, u16, u16)> + 'b {
+        self.vector
+            .iter()
+            .filter(|extraction_config| extraction_config.moving != Moving::Del)
+            .scan(0, |counter, extraction_config| {
+                let col_number_in_vec = *counter;
+                *counter += extraction_config.expected_columns;
+                Some((extraction_config, col_number_in_vec))
+            })
+            .filter_map(move |(extraction_config, col_number_in_vec)| {
+                match (&extraction_config.source, kind) {
+                    (Source::AtBasePrices(text, Matches::Exact), "base")
+                    | (Source::AtCurrPrices(text, Matches::Exact), "curr") => Some((
+                        text.as_str(),
+                        col_number_in_vec,
+                        extraction_config.expected_columns,
+                    )),
+                    _ => None,
+                }
+            })
+    }
+
+    fn count_col(vector: &[ExtractionConfig]) -> u16 {
+        vector
+            .iter()
+            .fold(0, |acc, extraction_config| match extraction_config.moving {
+                Moving::No => acc + extraction_config.expected_columns,
+                Moving::Yes => acc + extraction_config.expected_columns,
+                _ => acc,
+            })
+    }
+}
+#[test]
+fn excel_data_set_test() {
+    #[rustfmt::skip]
+        let vec_to_test = vec![
+            ExtractionConfig{rename: None,                           moving: Moving::No,  expected_columns: 1,  source: Source::InTableHeader("Объект")},
+            ExtractionConfig{rename: None,                           moving: Moving::Yes, expected_columns: 2,  source: Source::AtBasePrices("Накладные расходы".to_string(), Matches::Exact)},
+            ExtractionConfig{rename: None,                           moving: Moving::Yes, expected_columns: 3,  source: Source::AtBasePrices("Эксплуатация машин".to_string(), Matches::Exact)},
+            ExtractionConfig{rename: None,                           moving: Moving::Yes, expected_columns: 4,  source: Source::AtCurrPrices("Накладные расходы".to_string(), Matches::Exact)},
+            ExtractionConfig{rename: None,                           moving: Moving::Yes, expected_columns: 5,  source: Source::AtCurrPrices("Накладные".to_string(), Matches::Contains)},
+            ExtractionConfig{rename: Some("РЕНЕЙМ................"), moving: Moving::No,  expected_columns: 6,  source: Source::AtCurrPrices("Производство работ в зимнее время 4%".to_string(), Matches::Exact)},
+            ExtractionConfig{rename: Some("УДАЛИТЬ..............."), moving: Moving::Del, expected_columns: 99, source: Source::AtBasePrices("Производство работ в зимнее время 4%".to_string(), Matches::Exact)},
+            ExtractionConfig{rename: None,                           moving: Moving::Yes, expected_columns: 8,  source: Source::AtCurrPrices("Стоимость материальных ресурсов (всего)".to_string(), Matches::Exact)},
+        ];
+    let excel_data_set = ExcelDataSet::new(vec_to_test);
+
+    assert_eq!(&29, &excel_data_set.get_number_of_columns());
+    assert_eq!(
+        Some((6, 21)),
+        excel_data_set.get_index_and_address_by_columns(
+            "curr",
+            "Стоимость материальных ресурсов (всего)",
+            Matches::Exact
+        )
+    );
+    assert_eq!(
+        Some((4, 10)),
+        excel_data_set.get_index_and_address_by_columns(
+            "curr",
+            "Накладные расходы",
+            Matches::Contains
+        )
+    );
+    assert_eq!(
+        excel_data_set
+            .exact_price_columns("curr")
+            .collect::<Vec<_>>(),
+        vec![
+            ("Накладные расходы", 6, 4),
+            ("Производство работ в зимнее время 4%", 15, 6),
+            ("Стоимость материальных ресурсов (всего)", 21, 8),
+        ]
+    );
+}
+
+// Статистика итоговых строк, накапливаемая по мере сбора актов: по ней рассчитывается структура отчета,
+// поэтому сами акты к моменту создания отчета держать в памяти не нужно.
+// Для каждого имени - наибольшее число экземпляров
+#[derive(Debug, Default)]
+pub struct TotalsStats {
+    rows: Vec<TotalsRowInfo>, // в порядке первого появления
+    index_by_name: HashMap<String, usize>,
+}
+
+impl TotalsStats {
+    pub fn add_act(&mut self, act: &Act) {
+        for totalsrow in &act.data_of_totals {
+            let row_instance_count = totalsrow.row_number.len() as u16;
+
+            match self.index_by_name.get(&totalsrow.name) {
+                Some(&index) => {
+                    let info = &mut self.rows[index];
+                    info.row_instance_count = info.row_instance_count.max(row_instance_count);
+                    // строка акта с тем же именем, что и у строки процента, сохраняет оба вида цен
+                    info.is_percent &= totalsrow.is_percent;
+                }
+                None => {
+                    self.index_by_name
+                        .insert(totalsrow.name.clone(), self.rows.len());
+                    self.rows.push(TotalsRowInfo {
+                        row_name: totalsrow.name.clone(),
+                        row_instance_count,
+                        is_percent: totalsrow.is_percent,
+                    });
+                }
+            }
+        }
+    }
+}
+
+// Выступает в роли мета-структуры, которая классифицирует и хранит инструкции о том, как извлечь или обработать данные структуры Act для создания отчета.
+// Структура ExtractionConfig, в свою очередь, представляет собой отдельную такую инструкцию или параметры для отдельной группы данных из Act.
+pub struct WritingConfigs {
+    pub main_set: ExcelDataSet,
+    pub base_set: ExcelDataSet,
+    pub curr_set: ExcelDataSet,
+    pub totals_columns: HashMap<String, TotalsColumns>, // столбцы отчета по имени итоговой строки
+    pub percent_rows: HashSet<String>,                  // итоговые строки процентов надбавок
+}
+
+// Столбцы итоговой строки в отчете: (первый столбец, количество столбцов) для базовых и текущих цен
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TotalsColumns {
+    pub base: Option<(u16, u16)>,
+    pub curr: Option<(u16, u16)>,
+}
+
+impl<'a> WritingConfigs {
+    pub fn new(
+        totals_stats: TotalsStats,
+        totals_order: &[String],
+    ) -> Result<WritingConfigs, Error<'a>> {
+        #[rustfmt::skip]
+        let main_cfg: Vec<ExtractionConfig> = vec![
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::Calculate("Папка (ссылка)")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::Calculate("Файл (ссылка)")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::Calculate("Акт вид")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::Calculate("Акт №")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Акт дата")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Генподрядчик")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Субподрядчик")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Исполнитель")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::Calculate("Глава")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Объект")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Договор №")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Договор дата")},
+            // ExtractionConfig{rename: None,                                            moving: Moving::Yes,   expected_columns: 1, source: Source::AtBasePrices("Стоимость материальных ресурсов (всего)", Matches::Exact)},
+            // ExtractionConfig{rename: Some("Восстание машин"),                         moving: Moving::No, expected_columns: 1, source: Source::AtBasePrices("Эксплуатация машин", Matches::Exact)},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::Calculate("Смета №")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Смета наименование")},
+            ExtractionConfig{rename: Some("По смете в ц.2000г., руб."),           moving: Moving::No, expected_columns: 1,  source: Source::Calculate("По смете в ц.2000г.")},
+            ExtractionConfig{rename: Some("Выполнение работ в ц.2000г., руб."),   moving: Moving::No, expected_columns: 1,  source: Source::Calculate("Выполнение работ в ц.2000г.")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Отчетный период начало")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Отчетный период окончание")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Метод расчета")},
+            ExtractionConfig{rename: None,                                        moving: Moving::No, expected_columns: 1,  source: Source::InTableHeader("Затраты труда, чел.-час")},
+            ExtractionConfig{rename: None,                                        moving: Moving::Del, expected_columns: 1, source: Source::AtBasePrices("Всего с НР и СП (тек".to_string(), Matches::Contains)},
+            ExtractionConfig{rename: None,                                        moving: Moving::Del, expected_columns: 1, source: Source::AtCurrPrices("Всего с НР и СП (баз".to_string(), Matches::Contains)},
+            ExtractionConfig{rename: None,                                        moving: Moving::Del, expected_columns: 1, source: Source::AtBasePrices("Итого с К = 1".to_string(), Matches::Exact)},
+            ExtractionConfig{rename: None,                                        moving: Moving::Del, expected_columns: 1, source: Source::AtCurrPrices("Итого с К = 1".to_string(), Matches::Exact)},
+            // ExtractionConfig{rename: Some("РЕНЕЙМ................"),                  moving: Moving::No, expected_columns: 1, source: Source::AtBasePrices("Производство работ в зимнее время 4%", Matches::Exact)},
+            // ExtractionConfig{rename: None,                                            moving: Moving::Yes, expected_columns: 1, source: Source::AtBasePrices("ы", Matches::Contains)},
+        ];
+        // В векторе выше, перечислены далеко не все столбцы, что будут в акте (в акте может быть что угодно и при этом повторяться в неизвестном количестве).
+        // Думайте об этой структуре как о настроечной таблице, или даже центре управления: захотим удалим, переименуем, переставим местами.
+        // Позиция в векторе соответсвует позиции столбца в выходной форме (первая строка вектора будет первым столбцом в Excel от левого края).
+        // Однако, в итогах акта будут встречаться столбцы, порядок чередования которых останется тот, который существует в актах.
+        // Для excel-отчета потребуется три источника: шапка акта и два вида данных в его итогах: базовые и текущие цены.
+        // Отчет будет написан из этих 3 частей: main_set, base_set, curr_set
+        // Размер Excel-таблицы по горизонтали зависит от количества строк в итогах актов (допустим, обычно итоги имеют 17 строк,
+        // но навреняка найдется такой акт, который имеет 16, 18, 0 или, скажем, 40 строк в итогах. Потребуется какая-то логика, чтобы соотнести 40 строк одного акта
+        // с 17 строками других актов. Одна из задач: не сокращать эти 40 строк до 17 стандартных и выдать информацию пользователю без потерь.
+
+        let main_set = ExcelDataSet::new(main_cfg);
+
+        let totals_row_info_vec = Self::get_totals_row_info(totals_stats, totals_order);
+        let percent_rows = totals_row_info_vec
+            .iter()
+            .filter(|info| info.is_percent)
+            .map(|info| info.row_name.clone())
+            .collect();
+        let (vec_base_config, vec_curr_config) =
+            Self::get_price_part_configs(&main_set.vector, totals_row_info_vec);
+        let base_set = ExcelDataSet::new(vec_base_config);
+        let curr_set = ExcelDataSet::new(vec_curr_config);
+        let totals_columns = Self::get_totals_columns(&main_set, &base_set, &curr_set);
+        Ok(Self {
+            main_set,
+            base_set,
+            curr_set,
+            totals_columns,
+            percent_rows,
+        })
+    }
+
+    // Столбцы итоговых строк вычисляются один раз, чтобы при записи каждого значения не просматривать наборы столбцов.
+    // Итоговая строка ищется сначала в своем наборе (base_set или curr_set), затем в main_set
+    fn get_totals_columns(
+        main_set: &ExcelDataSet,
+        base_set: &ExcelDataSet,
+        curr_set: &ExcelDataSet,
+    ) -> HashMap<String, TotalsColumns> {
+        let mut totals_columns = HashMap::<String, TotalsColumns>::new();
+        let main_columns = main_set.get_number_of_columns();
+
+        for (kind, set, corr) in [
+            ("base", base_set, 0),
+            ("curr", curr_set, base_set.get_number_of_columns()),
+        ] {
+            for (name, col_number_in_vec, expected_columns) in set
+                .exact_price_columns(kind)
+                .chain(main_set.exact_price_columns(kind))
+            {
+                let columns = totals_columns.entry(name.to_string()).or_default();
+                let column_span = match kind {
+                    "base" => &mut columns.base,
+                    _ => &mut columns.curr,
+                };
+                // как и при поиске по наборам, выигрывает первое совпадение
+                if column_span.is_none() {
+                    *column_span =
+                        Some((col_number_in_vec + corr + main_columns, expected_columns));
+                }
+            }
+        }
+        totals_columns
+    }
+
+    // Порядок столбцов итогов не зависит от состава актов: сначала имена из канонического списка в его порядке
+    // (строка с процентом - сразу за своей строкой), затем прочие в порядке первого появления в актах.
+    // Так столбцы ежемесячных отчетов остаются на своих местах
+    fn get_totals_row_info(
+        totals_stats: TotalsStats,
+        totals_order: &[String],
+    ) -> Vec<TotalsRowInfo> {
+        // ключ сортировки: (позиция в каноническом списке, строка с процентом, порядок появления)
+        let order_key = |(first_seen, info): &(usize, TotalsRowInfo)| {
+            let base_name = match info.row_name.strip_suffix(TOTALS_PERCENT_SUFFIX) {
+                Some(base_name) if info.is_percent => base_name,
+                _ => info.row_name.as_str(),
+            };
+            let is_percent = info.is_percent;
+            match totals_order.iter().position(|name| name == base_name) {
+                Some(position) => (position, is_percent, 0),
+                None => (usize::MAX, false, *first_seen),
+            }
+        };
+
+        totals_stats
+            .rows
+            .into_iter()
+            .enumerate()
+            .sorted_by_key(order_key)
+            .map(|(_, info)| info)
+            .collect()
+    }
+
+    fn get_price_part_configs(
+        part_1: &[ExtractionConfig],
+        totals_row_info_vec: Vec<TotalsRowInfo>,
+    ) -> (Vec<ExtractionConfig>, Vec<ExtractionConfig>) {
+        let exclude_from_base = part_1
+            .iter()
+            .filter(|extraction_config| {
+                matches!(extraction_config.source, Source::AtBasePrices(_, _))
+            })
+            .collect::<Vec<_>>();
+
+        let exclude_from_curr = part_1
+            .iter()
+            .filter(|extraction_config| {
+                matches!(extraction_config.source, Source::AtCurrPrices(_, _))
+            })
+            .collect::<Vec<_>>();
+
+        let extraction_config = |exclude: &[&ExtractionConfig],
+                                 sh_outpdata: &TotalsRowInfo,
+                                 kind: &str|
+         -> Option<ExtractionConfig> {
+            let name = sh_outpdata.row_name.as_str();
+
+            let mut not_listed = true;
+            let mut required = false;
+            let mut rename = None;
+            let matches = Matches::Exact;
+
+            for item in exclude.iter() {
+                match item {
+                    ExtractionConfig {
+                        rename: set_name,
+                        moving: mov,
+                        source:
+                            Source::AtBasePrices(text, Matches::Exact)
+                            | Source::AtCurrPrices(text, Matches::Exact),
+                        ..
+                    } if text == name => {
+                        not_listed = false;
+                        if mov == &Moving::No {
+                            required = true;
+                            rename = *set_name;
+
+                            // println!(
+                            //     "other_print_parts: разрешил '{}' по точному совпадению имени, список досматриваться не будет",
+                            //     name
+                            // );
+                        }
+                        break;
+                    }
+                    ExtractionConfig {
+                        rename: set_name,
+                        moving: mov,
+                        source:
+                            Source::AtBasePrices(text, Matches::Contains)
+                            | Source::AtCurrPrices(text, Matches::Contains),
+                        ..
+                    } if name.contains(text) => {
+                        not_listed = false;
+                        if mov == &Moving::No {
+                            required = true;
+                            rename = *set_name;
+
+                            // println!(
+                            //     "other_print_parts: разрешил '{}' по НЕточному совпадению имени, список досматриваться не будет",
+                            //     name
+                            // );
+                        }
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+
+            if required || not_listed {
+                let moving = Moving::No;
+                let expected_columns = sh_outpdata.row_instance_count;
+
+                let source = match kind {
+                    "base" => Source::AtBasePrices(sh_outpdata.row_name.clone(), matches),
+                    "curr" => Source::AtCurrPrices(sh_outpdata.row_name.clone(), matches),
+                    _ => {
+                        unreachable!("Операция не над итоговыми строками акта (не покрыта match)")
+                    }
+                };
+
+                return Some(ExtractionConfig {
+                    rename,
+                    moving,
+                    expected_columns,
+                    source,
+                });
+            }
+            None
+        };
+
+        let (base_set, curr_set) = totals_row_info_vec.into_iter().fold(
+            (
+                Vec::<ExtractionConfig>::new(),
+                Vec::<ExtractionConfig>::new(),
+            ),
+            |mut acc, sh_outpdata| {
+                // у процента надбавки одно значение (в текущих ценах) и один столбец
+                let is_percent = sh_outpdata.is_percent;
+                if let Some(x) = extraction_config(&exclude_from_base, &sh_outpdata, "base")
+                    .filter(|_| !is_percent)
+                {
+                    acc.0.push(x)
+                };
+
+                if let Some(y) = extraction_config(&exclude_from_curr, &sh_outpdata, "curr") {
+                    acc.1.push(y)
+                };
+                acc
+            },
+        );
+        (base_set, curr_set)
+    }
+}
+
+// Столбцы основной таблицы, по которым считаются промежуточные итоги над заголовком
+#[rustfmt::skip]
+const SUBTOTAL_COLUMNS: [(&str, &str, Subtotal); 4] = [
+    ("calc",   "Файл (ссылка)",                 Subtotal::Count),
+    ("calc",   "По смете в ц.2000г.",           Subtotal::Sum),
+    ("calc",   "Выполнение работ в ц.2000г.",   Subtotal::Sum),
+    ("header", "Затраты труда, чел.-час",       Subtotal::Sum),
+];
+
+// cтруктура отчета. Report рассчитывает расположение столбцов и содержимое строк,
+// запись в файл выполняет вывод (по умолчанию - xlsx, формат выбирается настройкой)
+pub struct Report<O: ReportOutput = Box<dyn ReportOutput>> {
+    output: O,
+    pub writing_configs: WritingConfigs,
+    links: ReportLinks,
+    acts_column_count: usize,
+    pub body_syze_in_row: u32,
+}
+
+impl<O: ReportOutput> Report<O> {
+    pub fn new(
+        mut output: O,
+        totals_stats: TotalsStats,
+        totals_order: &[String],
+        links: ReportLinks,
+    ) -> Result<Report<O>, Error<'static>> {
+        let writing_configs = WritingConfigs::new(totals_stats, totals_order)?;
+
+        let acts_columns = Self::acts_columns(&writing_configs);
+
+        let issues_columns = [
+            ReportColumn::new(tr("Файл", "File"), 60.),
+            ReportColumn::new(tr("Лист", "Sheet"), 15.),
+            ReportColumn::new(tr("Уровень", "Level"), 15.),
+            ReportColumn::new(tr("Замечание", "Issue"), 100.),
+        ];
+
+        let long_totals_columns = [
+            ReportColumn::new(tr("Файл", "File"), 60.),
+            ReportColumn::new(tr("Лист", "Sheet"), 15.),
+            ReportColumn::new(column_title("Акт №"), 12.),
+            ReportColumn::new(tr("Итоговая строка", "Totals row"), 45.),
+            ReportColumn::new(tr("Имя в акте", "Name in the act"), 45.),
+            ReportColumn::new(tr("Экземпляр", "Instance"), 11.),
+            ReportColumn::new(tr("Вид цены", "Price kind"), 11.),
+            ReportColumn::new(tr("Значение", "Value"), 18.),
+            ReportColumn::new(tr("Строка акта", "Act row"), 12.),
+        ];
+
+        for (table, columns) in [
+            (ReportTable::Acts, &acts_columns[..]),
+            (ReportTable::Issues, &issues_columns[..]),
+            (ReportTable::LongTotals, &long_totals_columns[..]),
+        ] {
+            output.begin(table)?;
+            output.write_header_row(table, columns)?;
+        }
+
+        Ok(Report {
+            output,
+            writing_configs,
+            links,
+            acts_column_count: acts_columns.len(),
+            body_syze_in_row: 0,
+        })
+    }
+
+    pub fn write(mut self, act: &Act) -> Result<Self, Error<'static>> {
+        let mut row = vec![None; self.acts_column_count];
+        self.fill_header(&mut row, act);
+        for totalsrow in act.data_of_totals.iter() {
+            self.fill_totals(&mut row, totalsrow);
+        }
+        self.output.write_act_row(ReportTable::Acts, &row)?;
+
+        self.write_long_totals(act)?;
+        self.write_issues(act)?;
+        self.body_syze_in_row += 1;
+        Ok(self)
+    }
+
+    pub fn write_and_close_report(mut self) -> Result<(), Error<'static>> {
+        self.output.finish()
+    }
+
+    // Заголовки основной таблицы: столбцы шапки (main_set), затем итоги в базовых и текущих ценах
+    fn acts_columns(writing_configs: &WritingConfigs) -> Vec<ReportColumn> {
+        let main_set = &writing_configs.main_set;
+        let base_set = &writing_configs.base_set;
+        let curr_set = &writing_configs.curr_set;
+
+        let header_name: Vec<&ExtractionConfig> = main_set
+            .vector
+            .iter()
+            .filter(|extraction_config| {
+                extraction_config.moving != Moving::Del
+                    && !(extraction_config.moving == Moving::No
+                        && (matches!(extraction_config.source, Source::AtBasePrices(_, _))
+                            || matches!(extraction_config.source, Source::AtCurrPrices(_, _))))
+            })
+            .chain(base_set.vector.iter())
+            .chain(curr_set.vector.iter())
+            .collect();
+
+        let mut columns = Vec::<ReportColumn>::new();
+        for extraction_config in header_name.iter() {
+            let name = match &extraction_config.source {
+                Source::InTableHeader(x) => x,
+                Source::Calculate(x) => x,
+                Source::AtBasePrices(x, _) => &x[..],
+                Source::AtCurrPrices(x, _) => &x[..],
+            };
+            let is_percent = matches!(extraction_config.source, Source::AtCurrPrices(_, _))
+                && writing_configs.percent_rows.contains(name);
+            let prefix = match extraction_config.source {
+                _ if is_percent => None,
+                Source::AtBasePrices(_, _) => Some(tr("БЦ", "BP")),
+                Source::AtCurrPrices(_, _) => Some(tr("TЦ", "CP")),
+                _ => None,
+            };
+
+            let renaming_name = column_title(match extraction_config.rename {
+                Some(x) => x,
+                _ => name,
+            })
+            .to_owned();
+
+            let in_main_set = columns.len() < main_set.number_of_columns as usize;
+            // в итогах суммируются все столбцы итоговых строк, кроме процентов надбавок;
+            // столбцы шапки с итогами отмечаются ниже по SUBTOTAL_COLUMNS
+            let subtotal = if in_main_set || is_percent {
+                None
+            } else {
+                Some(Subtotal::Sum)
+            };
+            let name_in_subtotal_sums = SUBTOTAL_COLUMNS
+                .iter()
+                .any(|item| item.1 == name && item.2 == Subtotal::Sum);
+
+            let title = if let Some(x) = prefix {
+                x.to_owned() + " " + &renaming_name
+            } else {
+                renaming_name
+            };
+
+            //вычисляется ширина столбца excel при переносе строк в ячейке
+            let width = if in_main_set && !name_in_subtotal_sums {
+                let name_len = title.chars().count() / 2;
+                let mut first_line_len = 0;
+                for word in title.split(' ') {
+                    first_line_len += word.chars().count() + 1;
+                    if first_line_len > name_len {
+                        break;
+                    }
+                }
+                11.max(first_line_len) as f64
+            } else {
+                20.11
+            };
+
+            for _ in 0..extraction_config.expected_columns {
+                columns.push(ReportColumn {
+                    title: title.clone(),
+                    width,
+                    subtotal,
+                });
+            }
+        }
+
+        for (kind, name, subtotal) in SUBTOTAL_COLUMNS {
+            let (_, col) = main_set
+                .get_index_and_address_by_columns(kind, name, Matches::Exact)
+                .unwrap();
+            columns[col as usize].subtotal = Some(subtotal);
+        }
+        columns
+    }
+
+    // Каждое значение итоговой строки - отдельная строка таблицы: акт, имя строки, номер экземпляра, вид цены.
+    // Пустые значения не пишутся
+    fn write_long_totals(&mut self, act: &Act) -> Result<(), Error<'static>> {
+        let act_number = act
+            .names_of_header
+            .iter()
+            .position(|cell| cell.name == "Акт №")
+            .and_then(|index| act.data_of_header[index].as_ref());
+
+        for totalsrow in act.data_of_totals.iter() {
+            for (instance, (original_name, act_row)) in totalsrow
+                .original_names
+                .iter()
+                .zip(totalsrow.row_number.iter())
+                .enumerate()
+            {
+                // процент надбавки хранится в текущих ценах, но к виду цены не относится
+                let curr_kind = if totalsrow.is_percent {
+                    "%"
+                } else {
+                    tr("ТЦ", "Current")
+                };
+                let price_kinds = [
+                    (tr("БЦ", "Base"), &totalsrow.base_price),
+                    (curr_kind, &totalsrow.curr_price),
+                ];
+                for (price_kind, prices) in price_kinds {
+                    // в итоговых строках бывают только числа, логические значения и ошибки Excel
+                    let Some(value) = prices[instance].as_ref() else {
+                        continue;
+                    };
+                    let act_number = match act_number {
+                        Some(XlDataType::String(number)) => Some(text(number)),
+                        _ => None,
+                    };
+
+                    let row = [
+                        Some(text(&act.path)),
+                        Some(text(&act.sheetname)),
+                        act_number,
+                        Some(text(&totalsrow.name)),
+                        Some(text(original_name)),
+                        Some(ReportValue::Integer(instance as i64 + 1)),
+                        Some(text(price_kind)),
+                        Some(report_value(value)),
+                        Some(ReportValue::Integer(*act_row as i64)),
+                    ];
+                    self.output.write_act_row(ReportTable::LongTotals, &row)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Пропущенные при поиске файлов папки и файлы попадают в реестр замечаний как ошибки без листа
+    pub fn write_skipped(&mut self, skipped: &[SkippedEntry]) -> Result<(), Error<'static>> {
+        for entry in skipped {
+            let row = [
+                Some(text(&entry.path.to_string_lossy())),
+                None,
+                Some(text(Severity::Error.as_str())),
+                Some(text(&entry.reason)),
+            ];
+            self.output.write_act_row(ReportTable::Issues, &row)?;
+        }
+        Ok(())
+    }
+
+    fn write_issues(&mut self, act: &Act) -> Result<(), Error<'static>> {
+        for issue in act.validation.issues() {
+            let row = [
+                Some(text(&act.path)),
+                Some(text(&act.sheetname)),
+                Some(text(issue.severity.as_str())),
+                Some(text(&issue.message)),
+            ];
+            self.output.write_act_row(ReportTable::Issues, &row)?;
+        }
+        Ok(())
+    }
+
+    fn fill_header(&self, row: &mut [Option<ReportValue>], act: &Act) {
+        let mut column = 0_usize;
+        for item in self.writing_configs.main_set.vector.iter() {
+            if item.moving == Moving::Del {
+                continue;
+            }
+            let cell = &mut row[column];
+            column += item.expected_columns as usize;
+
+            // вычисляемые по шапке столбцы не смогут обработать ошибку Excel, она переносится как есть
+            if let Source::Calculate(name) = item.source {
+                let header_value = act
+                    .names_of_header
+                    .iter()
+                    .position(|desired_data| desired_data.name == name)
+                    .and_then(|index| act.data_of_header[index].as_ref());
+                if let Some(XlDataType::Error(text)) = header_value {
+                    *cell = Some(ReportValue::Error(text.clone()));
+                    continue;
+                }
+            }
+            if let Source::InTableHeader(name) = item.source {
+                let index = act
+                    .names_of_header
+                    .iter()
+                    .position(|desired_data| desired_data.name == name)
+                    .unwrap();
+
+                // значения шапки уже приведены к типу поля при сборе акта
+                *cell = act.data_of_header[index].as_ref().map(report_value);
+            }
+            if let Source::Calculate(name) = item.source {
+                match name {
+                    "Папка (ссылка)" => {
+                        if let Some(folder_path) = Path::new(&act.path).parent() {
+                            *cell = Some(ReportValue::Link {
+                                target: self.links.target(folder_path),
+                                text: folder_path.display().to_string(),
+                            });
+                        };
+                    }
+                    "Файл (ссылка)" => {
+                        let file_path = Path::new(&act.path);
+                        if let Some(file_name) = file_path.file_name() {
+                            *cell = Some(ReportValue::Link {
+                                target: self.links.target(file_path),
+                                text: file_name.to_string_lossy().into_owned(),
+                            });
+                        };
+                    }
+                    "Глава" => {
+                        let index_1 = act
+                            .names_of_header
+                            .iter()
+                            .position(|desired_data| desired_data.name == "Глава")
+                            .unwrap(); //_or(return Err("Ошибка в логике программы, сообщающая о необходимости исправления программного кода: \"Глава\" обязательно должна быть в DESIRED_DATA_ARRAY".to_owned()));
+
+                        let index_2 = act
+                            .names_of_header
+                            .iter()
+                            .position(|desired_data| desired_data.name == "Глава наименование")
+                            .unwrap(); //_or(return Err("Ошибка в логике программы, сообщающая о необходимости исправления программного кода: \"Глава наименование\" обязательно должна быть в DESIRED_DATA_ARRAY".to_owned()));
+
+                        if let (
+                            Some(XlDataType::String(temp_res_1)),
+                            Some(XlDataType::String(temp_res_2)),
+                        ) = (&act.data_of_header[index_1], &act.data_of_header[index_2])
+                        {
+                            if !(temp_res_1.is_empty() || temp_res_2.is_empty()) {
+                                *cell = Some(ReportValue::Text(format!(
+                                    "{} «{}»",
+                                    temp_res_1, temp_res_2
+                                )));
+                            }
+                        }
+                    }
+                    "Смета №" => {
+                        let index = act
+                            .names_of_header
+                            .iter()
+                            .position(|desired_data| desired_data.name == name)
+                            .unwrap(); //_or(return Err(format!("Ошибка в логике программы, сообщающая о необходимости исправления программного кода: \"{}\" обязательно должен быть перечислен в DESIRED_DATA_ARRAY", name)));
+                        let xl_data_type = &act.data_of_header[index];
+
+                        if let Some(XlDataType::String(txt)) = xl_data_type {
+                            *cell = Some(text(txt.trim_start_matches("Смета № ")));
+                        }
+                    }
+                    "По смете в ц.2000г." | "Выполнение работ в ц.2000г." =>
+                    {
+                        let index = act
+                            .names_of_header
+                            .iter()
+                            .position(|desired_data| desired_data.name == name)
+                            .unwrap(); //_or(return Err(format!("Ошибка в логике программы, сообщающая о необходимости исправления программного кода: \"{}\" обязательно должен быть перечислен в DESIRED_DATA_ARRAY", name)));
+                        let xl_data_type = &act.data_of_header[index];
+
+                        // денежная сумма уже переведена из тысяч рублей в рубли при сборе акта
+                        if let Some(XlDataType::Float(number)) = xl_data_type {
+                            *cell = Some(ReportValue::Number(*number));
+                        }
+                    }
+                    "Акт №" => {
+                        let index = act
+                            .names_of_header
+                            .iter()
+                            .position(|desired_data| desired_data.name == name)
+                            .unwrap();
+                        let xl_data_type = &act.data_of_header[index];
+
+                        if let Some(XlDataType::String(txt)) = xl_data_type {
+                            // if text.matches(['/']).count() == 3 {
+                            //    let text = &text.chars().take_while(|ch| *ch != '/').collect::<String>();
+                            *cell = Some(text(txt));
+                            // }
+                        }
+                    }
+                    "Акт вид" => {
+                        let index = act
+                            .names_of_header
+                            .iter()
+                            .position(|desired_data| desired_data.name == name)
+                            .unwrap();
+                        let xl_data_type = &act.data_of_header[index];
+
+                        if let Some(XlDataType::String(txt)) = xl_data_type {
+                            *cell = Some(ReportValue::Text(txt.to_lowercase()));
+                        }
+                    }
+                    _ => unreachable!("Данные не предусмотренные к записи (не покрыты match)"),
+                }
+            }
+        }
+    }
+
+    fn fill_totals(&self, row: &mut [Option<ReportValue>], totalsrow: &TotalsRow) {
+        let Some(columns) = self.writing_configs.totals_columns.get(&totalsrow.name) else {
+            return;
+        };
+
+        for (column_span, totalsrow_vec) in [
+            (columns.base, &totalsrow.base_price),
+            (columns.curr, &totalsrow.curr_price),
+        ] {
+            let Some((first_col, expected_columns)) = column_span else {
+                continue;
+            };
+            for (number_of_col, value) in totalsrow_vec
+                .iter()
+                .enumerate()
+                .take(expected_columns as usize)
+            {
+                // в итоговых строках бывают только числа, логические значения и ошибки Excel
+                if let Some(
+                    value @ (XlDataType::Float(_) | XlDataType::Bool(_) | XlDataType::Error(_)),
+                ) = value
+                {
+                    row[first_col as usize + number_of_col] = Some(report_value(value));
+                }
+            }
+        }
+    }
+} //end Report
+
+#[test]
+fn report_memory_output_test() {
+    use crate::extract::Severity;
+    use output::MemoryOutput;
+
+    let act_path = std::env::temp_dir().join("акты").join("акт #1.xlsm");
+    let mut act = Act::for_test(&act_path.to_string_lossy(), "Лист1");
+    act.set_header_value("Акт №", XlDataType::String("15".to_string()));
+    act.set_header_value("Объект", XlDataType::Error("#REF!".to_string()));
+    act.validation.push(Severity::Warning, "предупреждение");
+    act.start_row_of_totals = 38;
+    act.data_of_totals.push(TotalsRow {
+        name: "Накладные расходы".to_string(),
+        base_price: vec![Some(XlDataType::Float(10.)), None],
+        curr_price: vec![Some(XlDataType::Float(70.)), Some(XlDataType::Float(7.))],
+        row_number: vec![40, 45],
+        original_names: vec!["НР".to_string(), "Накладные расходы".to_string()],
+        is_percent: false,
+    });
+    act.data_of_totals.push(TotalsRow {
+        name: format!("Производство работ в зимнее время{TOTALS_PERCENT_SUFFIX}"),
+        base_price: vec![None],
+        curr_price: vec![Some(XlDataType::Float(2.8))],
+        row_number: vec![47],
+        original_names: vec!["Производство работ в зимнее время 2,8%".to_string()],
+        is_percent: true,
+    });
+
+    let mut totals_stats = TotalsStats::default();
+    totals_stats.add_act(&act);
+    let report = Report::new(
+        MemoryOutput::default(),
+        totals_stats,
+        &[],
+        ReportLinks::default(),
+    )
+    .unwrap();
+    let mut report = report.write(&act).unwrap();
+    let skipped = SkippedEntry {
+        path: std::env::temp_dir().join("закрытая папка"),
+        reason: "нет доступа".to_string(),
+    };
+    report.write_skipped(&[skipped]).unwrap();
+    report.output.finish().unwrap();
+    let output = &report.output;
+    assert!(output.finished);
+
+    let acts = &output.tables[&ReportTable::Acts];
+    assert_eq!(acts.rows.len(), 1);
+    assert_eq!(acts.rows[0].len(), acts.columns.len());
+    let Some(ReportValue::Link {
+        target,
+        text: link_text,
+    }) = &acts.rows[0][1]
+    else {
+        panic!("в столбце 'Файл (ссылка)' нет ссылки");
+    };
+    assert!(target.starts_with("file://") && target.ends_with("/акты/акт %231.xlsm"));
+    assert_eq!(link_text, "акт #1.xlsm");
+    assert_eq!(acts.columns[1].subtotal, Some(Subtotal::Count));
+
+    let column = |title: &str| {
+        acts.columns
+            .iter()
+            .position(|column| column.title == title)
+            .unwrap()
+    };
+    assert_eq!(acts.rows[0][column("Акт №")], Some(text("15")));
+    assert_eq!(
+        acts.rows[0][column("Объект")],
+        Some(ReportValue::Error("#REF!".to_string()))
+    );
+    let curr_col = column("TЦ Накладные расходы");
+    assert_eq!(acts.rows[0][curr_col], Some(ReportValue::Number(70.)));
+    assert_eq!(acts.rows[0][curr_col + 1], Some(ReportValue::Number(7.)));
+    assert_eq!(acts.columns[curr_col].subtotal, Some(Subtotal::Sum));
+
+    // у процента надбавки один столбец без вида цены и без итога
+    let percent_col = column("Производство работ в зимнее время, %");
+    assert_eq!(acts.rows[0][percent_col], Some(ReportValue::Number(2.8)));
+    assert_eq!(acts.columns[percent_col].subtotal, None);
+    assert_eq!(
+        acts.columns
+            .iter()
+            .filter(|column| column.title.ends_with(TOTALS_PERCENT_SUFFIX))
+            .count(),
+        1
+    );
+
+    // два значения в текущих ценах, одно в базовых и процент
+    let long_totals = &output.tables[&ReportTable::LongTotals];
+    assert_eq!(long_totals.rows.len(), 4);
+    assert_eq!(long_totals.rows[3][6], Some(text("%")));
+    let issues = &output.tables[&ReportTable::Issues];
+    assert_eq!(issues.rows.len(), 2);
+    assert_eq!(issues.rows[0][3], Some(text("предупреждение")));
+    assert_eq!(issues.rows[1][1], None);
+    assert_eq!(issues.rows[1][3], Some(text("нет доступа")));
+}
+
+#[test]
+fn report_raw_percent_named_row_test() {
+    use crate::extract::{normalize_totals_names, TotalsNameDictionary};
+    use output::MemoryOutput;
+
+    // без группировки по проценту строка акта с ", %" в конце имени остается обычной итоговой строкой
+    let name = format!("Прочие затраты{TOTALS_PERCENT_SUFFIX}");
+    let mut act = Act::for_test("акт.xlsm", "Лист1");
+    act.start_row_of_totals = 38;
+    act.data_of_totals.push(TotalsRow {
+        name: name.clone(),
+        base_price: vec![Some(XlDataType::Float(5.))],
+        curr_price: vec![Some(XlDataType::Float(35.))],
+        row_number: vec![40],
+        original_names: vec![name.clone()],
+        is_percent: false,
+    });
+    normalize_totals_names(&mut act, &mut TotalsNameDictionary::new(&[], false));
+    assert_eq!(act.data_of_totals.len(), 1);
+
+    let mut totals_stats = TotalsStats::default();
+    totals_stats.add_act(&act);
+    let report = Report::new(
+        MemoryOutput::default(),
+        totals_stats,
+        &[],
+        ReportLinks::default(),
+    )
+    .unwrap();
+    let mut report = report.write(&act).unwrap();
+    report.output.finish().unwrap();
+
+    let acts = &report.output.tables[&ReportTable::Acts];
+    let columns = acts
+        .columns
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| column.title.ends_with(&name))
+        .collect::<Vec<_>>();
+    assert_eq!(columns.len(), 2);
+    let values = columns
+        .iter()
+        .map(|(col, column)| (acts.rows[0][*col].clone(), column.subtotal))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        [
+            (Some(ReportValue::Number(5.)), Some(Subtotal::Sum)),
+            (Some(ReportValue::Number(35.)), Some(Subtotal::Sum)),
+        ]
+    );
+}
+
+fn text(text: &str) -> ReportValue {
+    ReportValue::Text(text.to_string())
+}
+
+fn report_value(value: &XlDataType) -> ReportValue {
+    match value {
+        XlDataType::String(text) => ReportValue::Text(text.clone()),
+        XlDataType::Float(number) => ReportValue::Number(*number),
+        XlDataType::Int(number) => ReportValue::Integer(*number),
+        XlDataType::Date(date) => ReportValue::Date(*date),
+        XlDataType::Bool(value) => ReportValue::Bool(*value),
+        XlDataType::Error(text) => ReportValue::Error(text.clone()),
+    }
+}
+
+// Заголовок столбца отчета на текущем языке. Имена итоговых строк актов - это данные, они не переводятся
+fn column_title(name: &str) -> &str {
+    #[rustfmt::skip]
+    let en = match name {
+        "Папка (ссылка)" =>                    "Folder (link)",
+        "Файл (ссылка)" =>                     "File (link)",
+        "Акт вид" =>                           "Act type",
+        "Акт №" =>                             "Act No.",
+        "Акт дата" =>                          "Act date",
+        "Генподрядчик" =>                      "General contractor",
+        "Субподрядчик" =>                      "Subcontractor",
+        "Исполнитель" =>                       "Performer",
+        "Глава" =>                             "Chapter",
+        "Объект" =>                            "Object",
+        "Договор №" =>                         "Contract No.",
+        "Договор дата" =>                      "Contract date",
+        "Смета №" =>                           "Estimate No.",
+        "Смета наименование" =>                "Estimate name",
+        "По смете в ц.2000г., руб." =>         "Per estimate at 2000 prices, RUB",
+        "Выполнение работ в ц.2000г., руб." => "Work performed at 2000 prices, RUB",
+        "Отчетный период начало" =>            "Reporting period start",
+        "Отчетный период окончание" =>         "Reporting period end",
+        "Метод расчета" =>                     "Calculation method",
+        "Затраты труда, чел.-час" =>           "Labour costs, man-hours",
+        _ => name,
+    };
+    tr(name, en)
+}
+
+fn variant_eq<T>(first: &T, second: &T) -> bool {
+    std::mem::discriminant(first) == std::mem::discriminant(second)
+}
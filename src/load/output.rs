@@ -0,0 +1,268 @@
+use super::sink::CellStyle;
+use super::{
+    csv::CsvOutput, json::JsonOutput, sheet_output::SheetOutput, sqlite::SqliteOutput,
+    xlsx::XlsxSink,
+};
+use crate::errors::Error;
+use crate::shared::lang::tr;
+use crate::shared::types::XlDate;
+use std::path::{Path, PathBuf};
+
+// Таблицы отчета. В xlsx это листы одной книги, в построчных форматах - отдельные файлы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReportTable {
+    Acts,       // основная таблица: строка на акт
+    Issues,     // реестр замечаний
+    LongTotals, // итоги в длинном формате
+}
+
+impl ReportTable {
+    pub fn name(self) -> &'static str {
+        match self {
+            ReportTable::Acts => "Лист1",
+            // Лист с замечаниями (предупреждения и сведения), с которыми акты попали в отчет
+            ReportTable::Issues => tr("Реестр замечаний", "Issues register"),
+            // Строка на каждое значение итоговой строки акта, удобно для фильтров и сводных таблиц
+            ReportTable::LongTotals => tr("Итоги (длинный формат)", "Totals (long format)"),
+        }
+    }
+}
+
+// Итог по столбцу над заголовком таблицы: для приемников, которые умеют формулы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subtotal {
+    Count, // количество строк (видимых при фильтре)
+    Sum,
+}
+
+// Столбец таблицы отчета. Расположение и подсказки оформления рассчитывает Report,
+// приемник использует из них то, что поддерживает его формат
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportColumn {
+    pub title: String,
+    pub width: f64,
+    pub subtotal: Option<Subtotal>,
+}
+
+impl ReportColumn {
+    pub fn new(title: &str, width: f64) -> ReportColumn {
+        ReportColumn {
+            title: title.to_string(),
+            width,
+            subtotal: None,
+        }
+    }
+}
+
+// Значение ячейки отчета. Вид значения одновременно служит подсказкой оформления
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportValue {
+    Text(String),
+    Number(f64),  // денежные суммы и прочие дробные числа
+    Integer(i64), // номера: экземпляр итоговой строки, строка акта
+    Date(XlDate),
+    Bool(bool),
+    Link { target: String, text: String },
+    Error(String), // ошибка Excel из исходного акта
+}
+
+impl ReportValue {
+    pub fn style(&self) -> CellStyle {
+        match self {
+            ReportValue::Text(_) | ReportValue::Integer(_) | ReportValue::Bool(_) => {
+                CellStyle::Plain
+            }
+            ReportValue::Number(_) => CellStyle::Number,
+            ReportValue::Date(_) => CellStyle::Date,
+            ReportValue::Link { .. } => CellStyle::Url,
+            ReportValue::Error(_) => CellStyle::Error,
+        }
+    }
+}
+
+// Вывод отчета. Report открывает таблицы, пишет их заголовки и строки, после чего завершает вывод.
+// Строки разных таблиц чередуются: за строкой акта следуют его замечания и значения итогов
+pub trait ReportOutput {
+    fn begin(&mut self, table: ReportTable) -> Result<(), Error<'static>>;
+
+    fn write_header_row(
+        &mut self,
+        table: ReportTable,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>>;
+
+    // Строка, относящаяся к акту; количество ячеек равно количеству столбцов таблицы
+    fn write_act_row(
+        &mut self,
+        table: ReportTable,
+        row: &[Option<ReportValue>],
+    ) -> Result<(), Error<'static>>;
+
+    fn finish(&mut self) -> Result<(), Error<'static>>;
+}
+
+impl<O: ReportOutput + ?Sized> ReportOutput for Box<O> {
+    fn begin(&mut self, table: ReportTable) -> Result<(), Error<'static>> {
+        (**self).begin(table)
+    }
+
+    fn write_header_row(
+        &mut self,
+        table: ReportTable,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>> {
+        (**self).write_header_row(table, columns)
+    }
+
+    fn write_act_row(
+        &mut self,
+        table: ReportTable,
+        row: &[Option<ReportValue>],
+    ) -> Result<(), Error<'static>> {
+        (**self).write_act_row(table, row)
+    }
+
+    fn finish(&mut self) -> Result<(), Error<'static>> {
+        (**self).finish()
+    }
+}
+
+// Формат файла отчета, ключ "output_format" файла настроек
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Xlsx,
+    Csv,
+    Json,
+    Sqlite,
+}
+
+impl OutputFormat {
+    pub fn parse(text: &str) -> Option<OutputFormat> {
+        match text.trim().to_lowercase().as_str() {
+            "xlsx" | "excel" => Some(OutputFormat::Xlsx),
+            "csv" => Some(OutputFormat::Csv),
+            "json" => Some(OutputFormat::Json),
+            "sqlite" | "sqlite3" => Some(OutputFormat::Sqlite),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Xlsx => ".xlsx",
+            OutputFormat::Csv => ".csv",
+            OutputFormat::Json => ".json",
+            OutputFormat::Sqlite => ".sqlite",
+        }
+    }
+
+    pub fn create(self, filepath: &Path) -> Box<dyn ReportOutput> {
+        match self {
+            OutputFormat::Xlsx => Box::new(SheetOutput::new(XlsxSink::new(), filepath)),
+            OutputFormat::Csv => Box::new(CsvOutput::new(filepath)),
+            OutputFormat::Json => Box::new(JsonOutput::new(filepath)),
+            OutputFormat::Sqlite => Box::new(SqliteOutput::new(filepath)),
+        }
+    }
+}
+
+// Построчные форматы пишут каждую таблицу в свой файл: основная таблица - в файл отчета,
+// прочие - рядом с ним, с именем таблицы после имени отчета
+pub fn table_file_path(filepath: &Path, table: ReportTable) -> PathBuf {
+    if table == ReportTable::Acts {
+        return filepath.to_path_buf();
+    }
+    let file_stem = filepath.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = filepath.extension().unwrap_or_default().to_string_lossy();
+    filepath.with_file_name(format!("{file_stem} - {}.{extension}", table.name()))
+}
+
+// Текстовое представление значения для построчных форматов
+pub fn value_to_text(value: &ReportValue) -> String {
+    match value {
+        ReportValue::Text(text) | ReportValue::Error(text) => text.clone(),
+        ReportValue::Number(number) => number.to_string(),
+        ReportValue::Integer(number) => number.to_string(),
+        ReportValue::Date(date) => {
+            format!("{:04}-{:02}-{:02}", date.year, date.month, date.day)
+        }
+        ReportValue::Bool(value) => value.to_string(),
+        ReportValue::Link { target, .. } => target.clone(),
+    }
+}
+
+// Вывод в память: все таблицы со строками, для проверки содержимого отчета в тестах
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemoryOutput {
+    pub tables: std::collections::HashMap<ReportTable, MemoryTable>,
+    pub finished: bool,
+}
+
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemoryTable {
+    pub columns: Vec<ReportColumn>,
+    pub rows: Vec<Vec<Option<ReportValue>>>,
+}
+
+#[cfg(test)]
+impl ReportOutput for MemoryOutput {
+    fn begin(&mut self, table: ReportTable) -> Result<(), Error<'static>> {
+        self.tables.insert(table, MemoryTable::default());
+        Ok(())
+    }
+
+    fn write_header_row(
+        &mut self,
+        table: ReportTable,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>> {
+        self.tables.entry(table).or_default().columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_act_row(
+        &mut self,
+        table: ReportTable,
+        row: &[Option<ReportValue>],
+    ) -> Result<(), Error<'static>> {
+        self.tables
+            .entry(table)
+            .or_default()
+            .rows
+            .push(row.to_vec());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error<'static>> {
+        self.finished = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutputFormat;
+    use crate::settings::Settings;
+
+    #[test]
+    fn output_format_setting() {
+        let output_format = |text: &str| Settings::from_text(text).map(|s| s.output_format);
+        assert_eq!(output_format("").unwrap(), OutputFormat::Xlsx);
+        assert_eq!(
+            output_format("output_format = CSV").unwrap(),
+            OutputFormat::Csv
+        );
+        assert_eq!(
+            output_format("output_format = json").unwrap(),
+            OutputFormat::Json
+        );
+        assert_eq!(
+            output_format("output_format = sqlite").unwrap(),
+            OutputFormat::Sqlite
+        );
+        assert!(output_format("output_format = xml").is_err());
+    }
+}
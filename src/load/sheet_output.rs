@@ -0,0 +1,207 @@
+use super::output::{ReportColumn, ReportOutput, ReportTable, ReportValue, Subtotal};
+use super::sink::{CellStyle, ReportSink, SheetId};
+use crate::errors::Error;
+use crate::shared::lang::tr;
+use crate::shared::utils;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Высота строки итогов над заголовком и строки заголовка таблицы, где итоги есть
+const SUBTOTAL_ROW_HEIGHT: f64 = 29.;
+const HEADER_ROW_HEIGHT: f64 = 46.5;
+// Номер последней строки листа Excel (с единицы)
+const LAST_SHEET_ROW: u32 = 1_048_576;
+// Предельная длина адреса и текста ссылки в формуле HYPERLINK
+const HYPERLINK_MAX_LEN: usize = 255;
+
+// Вывод отчета в книгу электронной таблицы: таблица - лист, строки пишутся ячейками через приемник.
+// Здесь решается, как таблица выглядит на листе: ширина столбцов, закрепление заголовка, автофильтр,
+// строка промежуточных итогов над заголовком и примечания к ячейкам с ошибками.
+// Строки листа выводятся строго сверху вниз: так приемник может не держать лист в памяти
+pub struct SheetOutput<S: ReportSink> {
+    sink: S,
+    filepath: PathBuf,
+    sheets: HashMap<ReportTable, SheetLayout>,
+}
+
+struct SheetLayout {
+    sheet: SheetId,
+    columns: Vec<ReportColumn>,
+    header_row: u32,
+    size_in_row: u32, // строк под заголовком
+}
+
+impl<S: ReportSink> SheetOutput<S> {
+    pub fn new(sink: S, filepath: &Path) -> SheetOutput<S> {
+        SheetOutput {
+            sink,
+            filepath: filepath.to_path_buf(),
+            sheets: HashMap::new(),
+        }
+    }
+
+    fn layout(&mut self, table: ReportTable) -> Result<&mut SheetLayout, Error<'static>> {
+        self.sheets
+            .get_mut(&table)
+            .ok_or(Error::XlsxwriterSheetCreation)
+    }
+
+    // Формулы считают только видимые при фильтре строки. Строка итогов пишется вместе с заголовком,
+    // до строк актов, поэтому диапазон формул открыт до конца листа: возвращаться к ней не придется
+    fn write_subtotals(
+        &mut self,
+        sheet: SheetId,
+        header_row: u32,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>> {
+        // первая строка под заголовком, номер с единицы
+        let start = header_row + 2;
+        let end = LAST_SHEET_ROW;
+
+        for (col, column) in columns.iter().enumerate() {
+            let col = col as u16;
+            let col_prefix = utils::get_xl_column_letter(col);
+            let (formula, style) = match column.subtotal {
+                Some(Subtotal::Count) => (
+                    format!(
+                        "=SUBTOTAL(103,{col_prefix}{start}:{col_prefix}{end})&\" {units}\"",
+                        units = tr("шт.", "pcs.")
+                    ),
+                    CellStyle::SubtotalText,
+                ),
+                Some(Subtotal::Sum) => (
+                    format!("=SUBTOTAL(109,{col_prefix}{start}:{col_prefix}{end})"),
+                    CellStyle::SubtotalNum,
+                ),
+                None => continue,
+            };
+            self.sink
+                .write_formula(sheet, header_row - 1, col, &formula, style)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: ReportSink> ReportOutput for SheetOutput<S> {
+    fn begin(&mut self, table: ReportTable) -> Result<(), Error<'static>> {
+        let sheet = self.sink.add_sheet(table.name())?;
+        self.sheets.insert(
+            table,
+            SheetLayout {
+                sheet,
+                columns: Vec::new(),
+                header_row: 0,
+                size_in_row: 0,
+            },
+        );
+        Ok(())
+    }
+
+    fn write_header_row(
+        &mut self,
+        table: ReportTable,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>> {
+        let layout = self.layout(table)?;
+        // над заголовком остается строка для промежуточных итогов, если они нужны
+        let with_subtotals = columns.iter().any(|column| column.subtotal.is_some());
+        layout.header_row = u32::from(with_subtotals);
+        layout.columns = columns.to_vec();
+        let (sheet, header_row) = (layout.sheet, layout.header_row);
+
+        if with_subtotals {
+            self.sink
+                .set_row_height(sheet, header_row - 1, SUBTOTAL_ROW_HEIGHT)?;
+            self.write_subtotals(sheet, header_row, columns)?;
+            self.sink
+                .set_row_height(sheet, header_row, HEADER_ROW_HEIGHT)?;
+        }
+        for (col, column) in columns.iter().enumerate() {
+            let col = col as u16;
+            self.sink
+                .write_string(sheet, header_row, col, &column.title, CellStyle::Header)?;
+            self.sink.set_column_width(sheet, col, column.width)?;
+        }
+        self.sink.freeze_panes(sheet, header_row + 1, 0)
+    }
+
+    fn write_act_row(
+        &mut self,
+        table: ReportTable,
+        row: &[Option<ReportValue>],
+    ) -> Result<(), Error<'static>> {
+        let layout = self.layout(table)?;
+        layout.size_in_row += 1;
+        let (sheet, row_number) = (layout.sheet, layout.header_row + layout.size_in_row);
+
+        for (col, value) in row.iter().enumerate() {
+            let Some(value) = value else {
+                continue;
+            };
+            let (col, style) = (col as u16, value.style());
+            match value {
+                ReportValue::Text(text) => self
+                    .sink
+                    .write_string(sheet, row_number, col, text, style)?,
+                ReportValue::Number(number) => self
+                    .sink
+                    .write_number(sheet, row_number, col, *number, style)?,
+                ReportValue::Integer(number) => {
+                    self.sink
+                        .write_number(sheet, row_number, col, *number as f64, style)?
+                }
+                ReportValue::Date(date) => self.sink.write_date(sheet, row_number, col, *date)?,
+                ReportValue::Bool(value) => {
+                    self.sink.write_boolean(sheet, row_number, col, *value)?
+                }
+                // длинная ссылка сломала бы формулу: пишется текст, а путь - в примечание к ячейке
+                ReportValue::Link { target, text }
+                    if target.chars().count() > HYPERLINK_MAX_LEN
+                        || text.chars().count() > HYPERLINK_MAX_LEN =>
+                {
+                    self.sink
+                        .write_string(sheet, row_number, col, text, CellStyle::Plain)?;
+                    let note = tr(
+                        format!("Путь слишком длинный для ссылки Excel:\n{target}"),
+                        format!("The path is too long for an Excel link:\n{target}"),
+                    );
+                    self.sink.write_note(sheet, row_number, col, &note)?
+                }
+                ReportValue::Link { target, text } => {
+                    // кавычки внутри строк формулы удваиваются
+                    let formula = format!(
+                        "=HYPERLINK(\"{}\", \"{}\")",
+                        target.replace('"', "\"\""),
+                        text.replace('"', "\"\"")
+                    );
+                    self.sink
+                        .write_formula(sheet, row_number, col, &formula, style)?
+                }
+                // ошибка Excel из исходного акта: текст ошибки красным и примечание к ячейке
+                ReportValue::Error(text) => {
+                    self.sink
+                        .write_string(sheet, row_number, col, text, style)?;
+                    let note = tr(
+                        "Ошибка Excel в исходной ячейке акта, подробности в реестре замечаний",
+                        "Excel error in the source cell of the act, see the issues register for details",
+                    );
+                    self.sink.write_note(sheet, row_number, col, note)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error<'static>> {
+        for layout in std::mem::take(&mut self.sheets).into_values() {
+            if layout.columns.is_empty() {
+                continue;
+            }
+            let last_row = layout.header_row + layout.size_in_row;
+            let last_col = layout.columns.len() as u16 - 1;
+            self.sink
+                .autofilter(layout.sheet, layout.header_row, 0, last_row, last_col)?;
+        }
+        self.sink.save(&self.filepath)
+    }
+}
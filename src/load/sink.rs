@@ -0,0 +1,104 @@
+use crate::errors::Error;
+use crate::shared::types::XlDate;
+use std::path::Path;
+
+// Оформление ячейки отчета. Report решает, что и куда писать, а как оформление выглядит в файле - дело приемника
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CellStyle {
+    Plain,
+    Number, // денежные суммы и прочие дробные числа
+    Url,    // гиперссылка формулой
+    Date,
+    Error,        // ошибка Excel из исходного акта
+    Header,       // заголовок столбца таблицы
+    SubtotalNum,  // строка промежуточных итогов над заголовком: суммы
+    SubtotalText, // строка промежуточных итогов над заголовком: количество строк
+}
+
+// Номер листа, выданный приемником при его создании
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SheetId(pub usize);
+
+// Приемник отчета: листы и ячейки файла. Отделяет Report от конкретной библиотеки записи xlsx
+pub trait ReportSink {
+    fn add_sheet(&mut self, name: &str) -> Result<SheetId, Error<'static>>;
+
+    fn write_string(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        text: &str,
+        style: CellStyle,
+    ) -> Result<(), Error<'static>>;
+
+    fn write_number(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        number: f64,
+        style: CellStyle,
+    ) -> Result<(), Error<'static>>;
+
+    fn write_formula(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        formula: &str,
+        style: CellStyle,
+    ) -> Result<(), Error<'static>>;
+
+    fn write_date(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        date: XlDate,
+    ) -> Result<(), Error<'static>>;
+
+    fn write_boolean(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        value: bool,
+    ) -> Result<(), Error<'static>>;
+
+    // Примечание к ячейке
+    fn write_note(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        text: &str,
+    ) -> Result<(), Error<'static>>;
+
+    fn set_column_width(
+        &mut self,
+        sheet: SheetId,
+        col: u16,
+        width: f64,
+    ) -> Result<(), Error<'static>>;
+
+    fn set_row_height(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        height: f64,
+    ) -> Result<(), Error<'static>>;
+
+    fn autofilter(
+        &mut self,
+        sheet: SheetId,
+        first_row: u32,
+        first_col: u16,
+        last_row: u32,
+        last_col: u16,
+    ) -> Result<(), Error<'static>>;
+
+    fn freeze_panes(&mut self, sheet: SheetId, row: u32, col: u16) -> Result<(), Error<'static>>;
+
+    fn save(&mut self, filepath: &Path) -> Result<(), Error<'static>>;
+}
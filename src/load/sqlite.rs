@@ -0,0 +1,211 @@
+use super::output::{value_to_text, ReportColumn, ReportOutput, ReportTable, ReportValue};
+use crate::errors::Error;
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Вывод отчета в базу SQLite: все таблицы отчета в одном файле, по таблице базы на таблицу отчета.
+// Столбцы без объявленного типа, значения хранят свой: суммы - REAL, номера и логические значения - INTEGER,
+// прочее - TEXT (даты в виде "гггг-мм-дд", ссылки - путем, ошибки Excel - текстом ошибки).
+// Все строки пишутся одной транзакцией, которая фиксируется при завершении вывода
+pub struct SqliteOutput {
+    filepath: PathBuf,
+    connection: Option<Connection>,
+    tables: HashMap<ReportTable, TableInsert>,
+}
+
+// Запрос вставки строки в таблицу, готовится по заголовку таблицы
+struct TableInsert {
+    sql: String,
+}
+
+impl SqliteOutput {
+    pub fn new(filepath: &Path) -> SqliteOutput {
+        SqliteOutput {
+            filepath: filepath.to_path_buf(),
+            connection: None,
+            tables: HashMap::new(),
+        }
+    }
+
+    fn sqlite_error(&self, err: rusqlite::Error) -> Error<'static> {
+        Error::SqliteWrite {
+            file_path: self.filepath.clone(),
+            err,
+        }
+    }
+
+    // База создается заново при открытии первой таблицы: отчет прошлого сбора не дополняется, а заменяется
+    fn connection(&mut self) -> Result<&Connection, Error<'static>> {
+        if self.connection.is_none() {
+            if self.filepath.exists() {
+                std::fs::remove_file(&self.filepath).map_err(|err| Error::ReportFileWrite {
+                    file_path: self.filepath.clone(),
+                    err,
+                })?;
+            }
+            let connection = Connection::open(&self.filepath)
+                .and_then(|connection| {
+                    connection.execute_batch("BEGIN")?;
+                    Ok(connection)
+                })
+                .map_err(|err| self.sqlite_error(err))?;
+            self.connection = Some(connection);
+        }
+        self.connection.as_ref().ok_or(Error::InternalLogic {
+            tech_descr: "Соединение с базой SQLite отчета не открыто.".to_string(),
+            err: None,
+        })
+    }
+}
+
+impl ReportOutput for SqliteOutput {
+    fn begin(&mut self, _table: ReportTable) -> Result<(), Error<'static>> {
+        self.connection().map(|_| ())
+    }
+
+    fn write_header_row(
+        &mut self,
+        table: ReportTable,
+        columns: &[ReportColumn],
+    ) -> Result<(), Error<'static>> {
+        let table_name = quote_identifier(table_identifier(table));
+        let column_names = unique_column_names(columns)
+            .iter()
+            .map(|name| quote_identifier(name))
+            .collect::<Vec<_>>();
+        let placeholders = vec!["?"; column_names.len()].join(", ");
+        let column_list = column_names.join(", ");
+
+        let create = format!("CREATE TABLE {table_name} ({column_list})");
+        let result = self.connection()?.execute(&create, []);
+        result.map_err(|err| self.sqlite_error(err))?;
+
+        self.tables.insert(
+            table,
+            TableInsert {
+                sql: format!("INSERT INTO {table_name} VALUES ({placeholders})"),
+            },
+        );
+        Ok(())
+    }
+
+    fn write_act_row(
+        &mut self,
+        table: ReportTable,
+        row: &[Option<ReportValue>],
+    ) -> Result<(), Error<'static>> {
+        let Some(insert) = self.tables.get(&table) else {
+            return Ok(());
+        };
+        let Some(connection) = self.connection.as_ref() else {
+            return Ok(());
+        };
+        let values = row
+            .iter()
+            .map(|value| value.as_ref().map_or(Value::Null, sql_value));
+        let result = connection
+            .prepare_cached(&insert.sql)
+            .and_then(|mut statement| statement.execute(params_from_iter(values)));
+        result.map(|_| ()).map_err(|err| self.sqlite_error(err))
+    }
+
+    fn finish(&mut self) -> Result<(), Error<'static>> {
+        self.tables.clear();
+        let Some(connection) = self.connection.take() else {
+            return Ok(());
+        };
+        let result = connection
+            .execute_batch("COMMIT")
+            .and_then(|_| connection.close().map_err(|(_, err)| err));
+        result.map_err(|err| self.sqlite_error(err))
+    }
+}
+
+// Имена таблиц базы не переводятся: по ним пишутся запросы
+fn table_identifier(table: ReportTable) -> &'static str {
+    match table {
+        ReportTable::Acts => "acts",
+        ReportTable::Issues => "issues",
+        ReportTable::LongTotals => "totals_long",
+    }
+}
+
+// У столбцов итогов с несколькими экземплярами одинаковые заголовки, а имена столбцов таблицы
+// должны различаться: повтор получает номер экземпляра
+fn unique_column_names(columns: &[ReportColumn]) -> Vec<String> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    columns
+        .iter()
+        .map(|column| {
+            let count = seen.entry(column.title.as_str()).or_insert(0);
+            *count += 1;
+            match *count {
+                1 => column.title.clone(),
+                instance => format!("{} ({instance})", column.title),
+            }
+        })
+        .collect()
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn sql_value(value: &ReportValue) -> Value {
+    match value {
+        ReportValue::Number(number) if number.is_finite() => Value::Real(*number),
+        ReportValue::Number(_) => Value::Null,
+        ReportValue::Integer(number) => Value::Integer(*number),
+        ReportValue::Bool(value) => Value::Integer(i64::from(*value)),
+        _ => Value::Text(value_to_text(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteOutput;
+    use crate::load::output::{ReportColumn, ReportOutput, ReportTable, ReportValue};
+    use rusqlite::Connection;
+
+    #[test]
+    fn sqlite_output_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("ks2_etl_sqlite_{}.sqlite", std::process::id()));
+        let columns = vec![
+            ReportColumn::new("Акт №", 10.),
+            ReportColumn::new("Накладные расходы", 10.),
+            ReportColumn::new("Накладные расходы", 10.),
+        ];
+
+        // повторный вывод заменяет базу, а не дополняет ее
+        for _ in 0..2 {
+            let mut output = SqliteOutput::new(&path);
+            output.begin(ReportTable::Acts).unwrap();
+            output
+                .write_header_row(ReportTable::Acts, &columns)
+                .unwrap();
+            let row = [
+                Some(ReportValue::Text("15".to_string())),
+                Some(ReportValue::Number(1234.5)),
+                None,
+            ];
+            output.write_act_row(ReportTable::Acts, &row).unwrap();
+            output.finish().unwrap();
+        }
+
+        let connection = Connection::open(&path).unwrap();
+        let rows: Vec<(String, f64, Option<f64>)> = connection
+            .prepare(r#"SELECT "Акт №", "Накладные расходы", "Накладные расходы (2)" FROM acts"#)
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        drop(connection);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(rows, vec![("15".to_string(), 1234.5, None)]);
+    }
+}
@@ -0,0 +1,401 @@
+use super::sink::{CellStyle, ReportSink, SheetId};
+use crate::errors::Error;
+use crate::shared::types::XlDate;
+use rust_xlsxwriter::{
+    Color, ExcelDateTime, Format, FormatAlign, FormatBorder, FormatUnderline, Note, Workbook,
+    Worksheet,
+};
+use std::path::Path;
+
+const NUM_FORMAT: &str = r#"#,##0.00____;-#,##0.00____;"-"____"#;
+const DATE_FORMAT: &str = "dd/mm/yyyy";
+const SUBTOTAL_FONT_COLOR: u32 = 0x1F497D;
+
+// Приемник отчета в файл xlsx (rust_xlsxwriter), форматы создаются один раз.
+// Листы создаются в режиме постоянной памяти: строка сбрасывается во временный файл, как только начата
+// следующая, поэтому строки каждого листа пишутся строго сверху вниз и к записанной строке не вернуться
+pub struct XlsxSink {
+    workbook: Workbook,
+    formats: XlsxFormats,
+}
+
+struct XlsxFormats {
+    plain: Format,
+    num: Format,
+    url: Format,
+    date: Format,
+    error: Format,
+    header: Format,
+    subtotal_num: Format,
+    subtotal_text: Format,
+}
+
+impl XlsxFormats {
+    fn new() -> XlsxFormats {
+        // Это формат для заголовка excel-таблицы
+        let header = Format::new()
+            .set_bold()
+            .set_text_wrap() // перенос строк внутри ячейки
+            .set_align(FormatAlign::Top)
+            .set_align(FormatAlign::Center)
+            .set_border(FormatBorder::Thin);
+
+        let subtotal_num = Format::new()
+            .set_bold()
+            .set_align(FormatAlign::Top)
+            .set_shrink() // автоуменьшение шрифта текста, если не влез в ячейку
+            .set_border(FormatBorder::Thin)
+            .set_font_size(12)
+            .set_font_color(Color::RGB(SUBTOTAL_FONT_COLOR))
+            .set_num_format(NUM_FORMAT);
+
+        let subtotal_text = Format::new()
+            .set_bold()
+            .set_align(FormatAlign::Top)
+            .set_align(FormatAlign::Center)
+            .set_shrink() // автоуменьшение шрифта текста, если не влез в ячейку
+            .set_border(FormatBorder::Thin)
+            .set_font_size(12)
+            .set_font_color(Color::RGB(SUBTOTAL_FONT_COLOR));
+
+        XlsxFormats {
+            plain: Format::new(),
+            num: Format::new().set_num_format(NUM_FORMAT),
+            url: Format::new()
+                .set_font_color(Color::Blue)
+                .set_underline(FormatUnderline::Single),
+            date: Format::new().set_num_format(DATE_FORMAT),
+            error: Format::new().set_font_color(Color::Red),
+            header,
+            subtotal_num,
+            subtotal_text,
+        }
+    }
+
+    fn get(&self, style: CellStyle) -> &Format {
+        match style {
+            CellStyle::Plain => &self.plain,
+            CellStyle::Number => &self.num,
+            CellStyle::Url => &self.url,
+            CellStyle::Date => &self.date,
+            CellStyle::Error => &self.error,
+            CellStyle::Header => &self.header,
+            CellStyle::SubtotalNum => &self.subtotal_num,
+            CellStyle::SubtotalText => &self.subtotal_text,
+        }
+    }
+}
+
+impl XlsxSink {
+    pub fn new() -> XlsxSink {
+        XlsxSink {
+            workbook: Workbook::new(),
+            formats: XlsxFormats::new(),
+        }
+    }
+
+    fn sheet(&mut self, sheet: SheetId) -> Result<&mut Worksheet, Error<'static>> {
+        self.sheet_and_format(sheet, CellStyle::Plain)
+            .map(|(worksheet, _)| worksheet)
+    }
+
+    fn sheet_and_format(
+        &mut self,
+        sheet: SheetId,
+        style: CellStyle,
+    ) -> Result<(&mut Worksheet, &Format), Error<'static>> {
+        let worksheet = self
+            .workbook
+            .worksheet_from_index(sheet.0)
+            .map_err(|_| Error::XlsxwriterSheetCreation)?;
+        Ok((worksheet, self.formats.get(style)))
+    }
+}
+
+impl ReportSink for XlsxSink {
+    fn add_sheet(&mut self, name: &str) -> Result<SheetId, Error<'static>> {
+        self.workbook
+            .add_worksheet_with_constant_memory()
+            .set_name(name)
+            .map_err(|_| Error::XlsxwriterSheetCreation)?;
+        let sheets_count = self.workbook.worksheets().len();
+        Ok(SheetId(sheets_count - 1))
+    }
+
+    fn write_string(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        text: &str,
+        style: CellStyle,
+    ) -> Result<(), Error<'static>> {
+        let (worksheet, format) = self.sheet_and_format(sheet, style)?;
+        worksheet
+            .write_string_with_format(row, col, text, format)
+            .map_err(Error::XlsxwriterCellWrite)?;
+        Ok(())
+    }
+
+    fn write_number(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        number: f64,
+        style: CellStyle,
+    ) -> Result<(), Error<'static>> {
+        let (worksheet, format) = self.sheet_and_format(sheet, style)?;
+        worksheet
+            .write_number_with_format(row, col, number, format)
+            .map_err(Error::XlsxwriterCellWrite)?;
+        Ok(())
+    }
+
+    fn write_formula(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        formula: &str,
+        style: CellStyle,
+    ) -> Result<(), Error<'static>> {
+        let (worksheet, format) = self.sheet_and_format(sheet, style)?;
+        worksheet
+            .write_formula_with_format(row, col, formula, format)
+            .map_err(Error::XlsxwriterCellWrite)?;
+        Ok(())
+    }
+
+    fn write_date(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        date: XlDate,
+    ) -> Result<(), Error<'static>> {
+        let datetime = ExcelDateTime::from_ymd(date.year as u16, date.month as u8, date.day as u8)
+            .map_err(Error::XlsxwriterCellWrite)?;
+        let (worksheet, format) = self.sheet_and_format(sheet, CellStyle::Date)?;
+        worksheet
+            .write_date_with_format(row, col, &datetime, format)
+            .map_err(Error::XlsxwriterCellWrite)?;
+        Ok(())
+    }
+
+    fn write_boolean(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        value: bool,
+    ) -> Result<(), Error<'static>> {
+        self.sheet(sheet)?
+            .write_boolean(row, col, value)
+            .map_err(Error::XlsxwriterCellWrite)?;
+        Ok(())
+    }
+
+    fn write_note(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        col: u16,
+        text: &str,
+    ) -> Result<(), Error<'static>> {
+        let note = Note::new(text).add_author_prefix(false);
+        self.sheet(sheet)?
+            .insert_note(row, col, &note)
+            .map_err(Error::XlsxwriterCellWrite)?;
+        Ok(())
+    }
+
+    fn set_column_width(
+        &mut self,
+        sheet: SheetId,
+        col: u16,
+        width: f64,
+    ) -> Result<(), Error<'static>> {
+        self.sheet(sheet)?
+            .set_column_width(col, width)
+            .map_err(Error::XlsxwriterFormatting)?;
+        Ok(())
+    }
+
+    fn set_row_height(
+        &mut self,
+        sheet: SheetId,
+        row: u32,
+        height: f64,
+    ) -> Result<(), Error<'static>> {
+        self.sheet(sheet)?
+            .set_row_height(row, height)
+            .map_err(Error::XlsxwriterFormatting)?;
+        Ok(())
+    }
+
+    fn autofilter(
+        &mut self,
+        sheet: SheetId,
+        first_row: u32,
+        first_col: u16,
+        last_row: u32,
+        last_col: u16,
+    ) -> Result<(), Error<'static>> {
+        self.sheet(sheet)?
+            .autofilter(first_row, first_col, last_row, last_col)
+            .map_err(Error::XlsxwriterFormatting)?;
+        Ok(())
+    }
+
+    fn freeze_panes(&mut self, sheet: SheetId, row: u32, col: u16) -> Result<(), Error<'static>> {
+        self.sheet(sheet)?
+            .set_freeze_panes(row, col)
+            .map_err(Error::XlsxwriterFormatting)?;
+        Ok(())
+    }
+
+    fn save(&mut self, filepath: &Path) -> Result<(), Error<'static>> {
+        self.workbook
+            .save(filepath)
+            .map_err(|err| Error::XlsxwriterWorkbookClose {
+                wb_name: filepath
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                err,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XlsxSink;
+    use crate::load::output::{ReportColumn, ReportOutput, ReportTable, ReportValue, Subtotal};
+    use crate::load::sheet_output::SheetOutput;
+    use crate::load::sink::{CellStyle, ReportSink};
+    use calamine::{open_workbook, DataType, Reader, Xlsx};
+
+    #[test]
+    fn xlsx_sink_round_trip() {
+        let mut sink = XlsxSink::new();
+        let sheet = sink.add_sheet("Отчет").unwrap();
+        sink.write_string(sheet, 0, 0, "Акт №", CellStyle::Header)
+            .unwrap();
+        sink.write_number(sheet, 1, 0, 1234.5, CellStyle::Number)
+            .unwrap();
+        sink.write_note(sheet, 1, 0, "примечание").unwrap();
+        sink.autofilter(sheet, 0, 0, 1, 0).unwrap();
+        sink.freeze_panes(sheet, 1, 0).unwrap();
+
+        let path = std::env::temp_dir().join(format!("ks2_etl_sink_{}.xlsx", std::process::id()));
+        sink.save(&path).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        let range = workbook.worksheet_range("Отчет").unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            range.get_value((0, 0)).and_then(|x| x.get_string()),
+            Some("Акт №")
+        );
+        assert_eq!(
+            range.get_value((1, 0)).and_then(|x| x.get_float()),
+            Some(1234.5)
+        );
+    }
+
+    // Лист в режиме постоянной памяти принимает строки только сверху вниз: строка итогов над заголовком
+    // пишется до строк актов и не теряется
+    #[test]
+    fn sheet_output_writes_rows_top_down() {
+        let path =
+            std::env::temp_dir().join(format!("ks2_etl_sheet_output_{}.xlsx", std::process::id()));
+        let mut output = SheetOutput::new(XlsxSink::new(), &path);
+        let columns = vec![
+            ReportColumn::new("Акт №", 10.),
+            ReportColumn {
+                subtotal: Some(Subtotal::Sum),
+                ..ReportColumn::new("Сумма", 10.)
+            },
+        ];
+        output.begin(ReportTable::Acts).unwrap();
+        output
+            .write_header_row(ReportTable::Acts, &columns)
+            .unwrap();
+        for (number, sum) in [("1", 10.), ("2", 20.5)] {
+            let row = [
+                Some(ReportValue::Text(number.to_string())),
+                Some(ReportValue::Number(sum)),
+            ];
+            output.write_act_row(ReportTable::Acts, &row).unwrap();
+        }
+        output.finish().unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        let name = ReportTable::Acts.name();
+        let range = workbook.worksheet_range(name).unwrap();
+        let formulas = workbook.worksheet_formula(name).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            formulas.get_value((0, 1)).map(String::as_str),
+            Some("SUBTOTAL(109,B3:B1048576)")
+        );
+        assert_eq!(
+            range.get_value((1, 1)).and_then(|x| x.get_string()),
+            Some("Сумма")
+        );
+        assert_eq!(
+            range.get_value((2, 0)).and_then(|x| x.get_string()),
+            Some("1")
+        );
+        assert_eq!(
+            range.get_value((3, 1)).and_then(|x| x.get_float()),
+            Some(20.5)
+        );
+    }
+
+    #[test]
+    fn sheet_output_writes_long_links_as_text() {
+        let path =
+            std::env::temp_dir().join(format!("ks2_etl_long_links_{}.xlsx", std::process::id()));
+        let mut output = SheetOutput::new(XlsxSink::new(), &path);
+        let link = |target: String| {
+            Some(ReportValue::Link {
+                target,
+                text: "акт.xlsm".to_string(),
+            })
+        };
+        let long_target = format!("file:///D:/{}/акт.xlsm", "Объекты".repeat(40));
+        output.begin(ReportTable::Acts).unwrap();
+        output
+            .write_header_row(ReportTable::Acts, &[ReportColumn::new("Файл", 10.)])
+            .unwrap();
+        for target in ["file:///D:/Объекты/акт.xlsm".to_string(), long_target] {
+            output
+                .write_act_row(ReportTable::Acts, &[link(target)])
+                .unwrap();
+        }
+        output.finish().unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        let name = ReportTable::Acts.name();
+        let range = workbook.worksheet_range(name).unwrap();
+        let formulas = workbook.worksheet_formula(name).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            formulas.get_value((1, 0)).map(String::as_str),
+            Some(r#"HYPERLINK("file:///D:/Объекты/акт.xlsm", "акт.xlsm")"#)
+        );
+        assert!(formulas
+            .get_value((2, 0))
+            .map_or(true, |formula| formula.is_empty()));
+        assert_eq!(
+            range.get_value((2, 0)).and_then(|x| x.get_string()),
+            Some("акт.xlsm")
+        );
+    }
+}
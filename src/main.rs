@@ -1,28 +1,54 @@
 use console::{Style, Term}; // для очистки консоли перед выводом полезных сообщений
-use std::env;
-use std::path;
 use std::thread; // для засыпания на секунду-две при печати сообщений
 use std::time::Duration; // для засыпания на секунду-две при печати сообщений // имя ".exe" будет присвоено файлу Excel
-mod shared;
 mod errors;
 mod extract;
 mod load;
+mod settings;
+mod shared;
 mod ui;
-use crate::shared::constants::{SUCCESS_PAUSE_DURATION, XL_FILE_EXTENSION};
+mod watch;
 use crate::errors::Error;
 use crate::extract::Act;
-use crate::extract::{ExtractedBooks, Sheet};
-use crate::load::Report;
+use crate::extract::{ActStore, Book, ExtractedBooks, Sheet, SkippedEntry, TotalsNameDictionary};
+use crate::load::{Report, ReportLinks, TotalsStats};
+use crate::settings::Settings;
+use crate::shared::constants::{IGNORE_FILE_NAME, SUCCESS_PAUSE_DURATION, XL_FILE_EXTENSION};
+use crate::shared::lang::{self, tr};
+use crate::shared::utils;
 
 fn main() {
     Term::stdout().set_title("«Ks2 etl»,  v".to_string() + env!("CARGO_PKG_VERSION"));
+    let settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(err) => {
+            display_error_and_wait(err);
+            Settings::default()
+        }
+    };
+    lang::set_current(settings.lang);
+
     ui::display_first_lines(true);
     ui::display_help();
     let cyan = Style::new().cyan();
     let red = Style::new().red();
     'main_loop: loop {
         let (path, user_entered_sh_name) = match ui::user_input() {
-            Ok(x) => x,
+            Ok(ui::UserRequest::Collect(path, sh_name)) => (path, sh_name),
+            Ok(ui::UserRequest::Explain(path, sh_name)) => {
+                let explanation = Book::new(path).and_then(|book| extract::explain(book, &sh_name));
+                match explanation {
+                    Ok(text) => ui::display_formatted_text(&format!("\n{text}"), None),
+                    Err(err) => display_error_and_wait(err),
+                }
+                continue 'main_loop;
+            }
+            Ok(ui::UserRequest::Watch(path, sh_name)) => {
+                if let Err(err) = watch::watch(&path, &sh_name, &settings) {
+                    display_error_and_wait(err);
+                }
+                continue 'main_loop;
+            }
             Err(err) => {
                 display_error_and_wait(err);
                 continue 'main_loop;
@@ -33,51 +59,130 @@ fn main() {
         // let path = std::path::PathBuf::from(r"C:\Users\User\rust\ks2_etl\02-01.1-0239С-2С-И3-17-01-2023 - копия.xlsm".to_string());
         // let path = std::path::PathBuf::from(r"C:\Users\User\rust\ks2_etl\02-01.1-0239С-2С-И3-17-01-2023 — копия.xlsm".to_string());
 
-        let string_report_path = env::args()
-            .next()
-            .unwrap()
-            .trim_end_matches(".exe")
-            .to_owned()
-            + ".xlsx";
-
-        let report_path = path::PathBuf::from(string_report_path);
-
-        let wraped_books_vec = ExtractedBooks::new(&path).map(|extracted_xl_books| {
-            if path.is_dir() {
-                let file_count_total =
-                    extracted_xl_books.books.len() + extracted_xl_books.file_count_excluded;
-                let base_msg = format!(
-                    "Обнаружено {} файлов с расширением \"{}\".",
-                    file_count_total, XL_FILE_EXTENSION
-                );
+        let report_path = utils::get_path_next_to_exe(settings.output_format.extension());
 
-                let footer_msg = if extracted_xl_books.file_count_excluded > 0 {
+        let discovery = &settings.discovery;
+        let is_manifest = ExtractedBooks::is_manifest(&path);
+        let extracted = if is_manifest {
+            ExtractedBooks::from_manifest(&path, discovery)
+        } else {
+            ExtractedBooks::new(&path, discovery)
+        };
+        let wraped_books_vec = extracted.map(|extracted_xl_books| {
+            if is_manifest {
+                let found_count = extracted_xl_books.file_paths.len();
+                let entry_count = found_count + extracted_xl_books.skipped.len();
+                let msg = tr(
+                    format!("\nВ списке файлов к сбору {entry_count} записей, найдено файлов: {found_count}."),
+                    format!("\nThe list of files to collect has {entry_count} entries, files found: {found_count}."),
+                );
+                ui::display_formatted_text(&msg, None);
+            } else if path.is_dir() {
+                let file_count_total = extracted_xl_books.file_paths.len()
+                    + extracted_xl_books.file_count_excluded
+                    + extracted_xl_books.file_count_filtered
+                    + extracted_xl_books.file_count_ignored;
+                let base_msg = tr(
                     format!(
-                        r#"Из них {} помечены "@" для исключения."#,
-                        extracted_xl_books.file_count_excluded
-                    )
-                } else {
-                    "Среди них нет файлов, помеченных как исключенные.".to_string()
-                };
-
-                let full_msg = format!(
-                    "\n{}\n{}",
-                    base_msg,
-                    if file_count_total == 0 {
-                        "".to_string()
-                    } else {
-                        footer_msg
-                    }
+                        "Обнаружено {} файлов с расширением \"{}\".",
+                        file_count_total, XL_FILE_EXTENSION
+                    ),
+                    format!(
+                        "Found {} files with the \"{}\" extension.",
+                        file_count_total, XL_FILE_EXTENSION
+                    ),
                 );
 
+                let mut footer_lines = Vec::new();
+                if extracted_xl_books.file_count_excluded > 0 {
+                    footer_lines.push(tr(
+                        format!(
+                            r#"Из них {} помечены "{}" для исключения."#,
+                            extracted_xl_books.file_count_excluded, discovery.exclusion_marker
+                        ),
+                        format!(
+                            r#"Of these, {} are marked with "{}" for exclusion."#,
+                            extracted_xl_books.file_count_excluded, discovery.exclusion_marker
+                        ),
+                    ));
+                } else if !discovery.exclusion_marker.is_empty() {
+                    footer_lines.push(
+                        tr(
+                            "Среди них нет файлов, помеченных как исключенные.",
+                            "None of them are marked as excluded.",
+                        )
+                        .to_string(),
+                    );
+                }
+                if extracted_xl_books.file_count_ignored > 0 {
+                    footer_lines.push(tr(
+                        format!(
+                            "Исключены правилами файлов {}: {}.",
+                            IGNORE_FILE_NAME, extracted_xl_books.file_count_ignored
+                        ),
+                        format!(
+                            "Ignored by {} files: {}.",
+                            IGNORE_FILE_NAME, extracted_xl_books.file_count_ignored
+                        ),
+                    ));
+                }
+                if extracted_xl_books.file_count_filtered > 0 {
+                    footer_lines.push(tr(
+                        format!(
+                            "Не подходят под шаблоны отбора: {}.",
+                            extracted_xl_books.file_count_filtered
+                        ),
+                        format!(
+                            "Not matching the selection patterns: {}.",
+                            extracted_xl_books.file_count_filtered
+                        ),
+                    ));
+                }
+
+                let mut full_msg = format!("\n{base_msg}");
+                if file_count_total != 0 {
+                    for line in footer_lines {
+                        full_msg = format!("{full_msg}\n{line}");
+                    }
+                }
+                if let Some(description) = discovery.describe() {
+                    full_msg = format!("{full_msg}\n{description}");
+                }
+
                 ui::display_formatted_text(&full_msg, None);
             }
 
-            extracted_xl_books.books
+            // сбор по папке неполон: пользователь должен узнать об этом до отчета
+            if !extracted_xl_books.skipped.is_empty() {
+                let mut skipped_msg = tr(
+                    format!(
+                        "\nПропущено при поиске файлов: {} (перечислены в реестре замечаний отчета):",
+                        extracted_xl_books.skipped.len()
+                    ),
+                    format!(
+                        "\nSkipped while searching for files: {} (listed in the issues register of the report):",
+                        extracted_xl_books.skipped.len()
+                    ),
+                );
+                for entry in &extracted_xl_books.skipped {
+                    skipped_msg = format!(
+                        "{skipped_msg}\n{}: {}",
+                        entry.path.display(),
+                        entry.reason
+                    );
+                }
+                ui::display_formatted_text(&skipped_msg, Some(&red));
+            }
+
+            (
+                extracted_xl_books.file_paths,
+                extracted_xl_books.skipped,
+                extracted_xl_books.sheet_names,
+            )
         });
 
-        let books_vec = match wraped_books_vec {
-            Ok(books_vec) => books_vec,
+        let (books_vec, mut skipped_entries, sheet_names) = match wraped_books_vec {
+            Ok(books) => books,
             Err(err) => {
                 display_error_and_wait(err);
                 continue 'main_loop;
@@ -85,59 +190,96 @@ fn main() {
         };
 
         if books_vec.is_empty() {
-            ui::display_formatted_text("Нет файлов к сбору.", Some(&red));
+            ui::display_formatted_text(
+                tr("Нет файлов к сбору.", "No files to collect."),
+                Some(&red),
+            );
             thread::sleep(Duration::from_secs(SUCCESS_PAUSE_DURATION));
             continue 'main_loop;
         }
-        
-        ui::display_formatted_text("\nИдет анализ отобранных excel-файлов, ожидайте...", None);
-
-        let acts_vec = {
-            let mut temp_acts_vec = Vec::new();
-            for item in books_vec.into_iter() {
-                let book = match item {
-                    Ok(x) => x,
-                    Err(err) => {
-                        let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет анализ excel-файлов
-                        display_error_and_wait(err);
-                        continue 'main_loop;
-                    }
-                };
-
-                let sheet = match Sheet::new(book, &user_entered_sh_name) {
-                    Ok(x) => x,
-                    Err(err) => {
-                        let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет анализ excel-файлов
-                        display_error_and_wait(err);
-                        continue 'main_loop;
-                    }
-                };
-
-                let act = match Act::new(sheet) {
-                    Ok(x) => x,
-                    Err(err) => {
-                        let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет анализ excel-файлов
-                        display_error_and_wait(err);
-                        continue 'main_loop;
-                    }
-                };
 
-                temp_acts_vec.push(act);
+        ui::display_formatted_text(
+            tr(
+                "\nИдет анализ отобранных excel-файлов, ожидайте...",
+                "\nAnalysing the selected excel files, please wait...",
+            ),
+            None,
+        );
+
+        // одинаковые по смыслу итоговые строки разных актов должны попасть в один столбец отчета
+        let mut totals_names =
+            TotalsNameDictionary::new(&settings.totals_aliases, settings.group_percent_totals);
+        let totals_order = extract::totals_column_order(&settings.totals_order, &mut totals_names);
+
+        // Акты не копятся в памяти: каждый собранный акт откладывается во временный файл,
+        // а для расчета структуры отчета остается лишь статистика его итоговых строк
+        let mut act_store = match ActStore::new() {
+            Ok(x) => x,
+            Err(err) => {
+                let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет анализ excel-файлов
+                display_error_and_wait(err);
+                continue 'main_loop;
             }
-            temp_acts_vec
         };
+        let mut totals_stats = TotalsStats::default();
 
-        let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет анализ excel-файлов
-        ui::display_formatted_text("\nИдет расчет структуры результирующего excel-отчета, ожидайте...", None);
+        // Файл, который не удалось разобрать, не прерывает сбор: он попадает в реестр замечаний,
+        // чтобы все проблемы стали видны за один проход
+        let mut not_collected = Vec::new();
 
-        // "При вызове new() для Report требуется вектор актов. Это связанно с тем, что xlsxwriter
-        // не может вставлять столбцы и не сможет переносить то, что им уже записано (т.к. не умеет читать Excel),
-        // что предполагает необходимость установить общее количество столбцов, и их порядок до того как начнется запись.
-        // Получается, на протяжении работы программы в Report акты передаются дважды:
-        // при создании формы отчета для создания выборки всех названий, что встречаются в итогах,
-        // а второй раз акт в Report будет передан циклом записи."
+        for file_path in books_vec.into_iter() {
+            // лист, заданный для файла в списке к сбору, важнее введенного
+            let sh_name = sheet_names.get(&file_path).unwrap_or(&user_entered_sh_name);
+            let parsed = Book::new(file_path.clone())
+                .map_err(|err| err.to_string())
+                .and_then(|book| Sheet::new(book, sh_name).map_err(|err| err.to_string()))
+                .and_then(|sheet| Act::new(sheet).map_err(|err| err.to_string()));
+            let mut act = match parsed {
+                Ok(x) => x,
+                Err(reason) => {
+                    skipped_entries.push(SkippedEntry {
+                        path: file_path.clone(),
+                        reason,
+                    });
+                    not_collected.push(file_path);
+                    continue;
+                }
+            };
+
+            extract::normalize_totals_names(&mut act, &mut totals_names);
+            totals_stats.add_act(&act);
+
+            if let Err(err) = act_store.push(&act) {
+                let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет анализ excel-файлов
+                display_error_and_wait(err);
+                continue 'main_loop;
+            }
+        }
 
-        let wrappedreport = Report::new(&report_path, &acts_vec);
+        let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет анализ excel-файлов
+        ui::display_formatted_text(
+            tr(
+                "\nИдет расчет структуры результирующего excel-отчета, ожидайте...",
+                "\nCalculating the structure of the resulting excel report, please wait...",
+            ),
+            None,
+        );
+
+        // Запись отчета потоковая: нельзя вставлять столбцы и переносить то, что уже записано,
+        // поэтому общее количество столбцов и их порядок устанавливаются до того, как начнется запись.
+        // Для этого Report::new() получает статистику итоговых строк всех актов, а сами акты
+        // затем по одному читаются из временного файла циклом записи
+        let wrappedreport = Report::new(
+            settings.output_format.create(&report_path),
+            totals_stats,
+            &totals_order,
+            ReportLinks::new(
+                &report_path,
+                settings.relative_links,
+                settings.link_root.as_deref(),
+                &settings.link_maps,
+            ),
+        );
         let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что идет вычисление структуры excel-отчета
 
         let mut report = match wrappedreport {
@@ -148,10 +290,25 @@ fn main() {
             }
         };
 
-        ui::display_formatted_text("\nИдет запись результирующего excel-отчета, ожидайте...", None);
+        ui::display_formatted_text(
+            tr(
+                "\nИдет запись результирующего excel-отчета, ожидайте...",
+                "\nWriting the resulting excel report, please wait...",
+            ),
+            None,
+        );
+
+        let stored_acts = match act_store.into_acts() {
+            Ok(x) => x,
+            Err(err) => {
+                display_error_and_wait(err);
+                continue 'main_loop;
+            }
+        };
 
-        for act in acts_vec.iter() {
-            match report.write(act) {
+        for act in stored_acts {
+            let written = act.and_then(|act| report.write(&act));
+            match written {
                 Ok(updated_report) => report = updated_report,
                 Err(err) => {
                     let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что записывается Excel
@@ -161,22 +318,54 @@ fn main() {
             }
         }
 
+        if let Err(err) = report.write_skipped(&skipped_entries) {
+            let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что записывается Excel
+            display_error_and_wait(err);
+            continue 'main_loop;
+        }
+
         let files_counter = report.body_syze_in_row;
 
-        if let Err(err) = report.write_and_close_report(&report_path) {
+        if let Err(err) = report.write_and_close_report() {
             let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что записывается Excel
             display_error_and_wait(err);
             continue 'main_loop;
         }
 
         let _ = Term::stdout().clear_last_lines(2); // удаляется сообщение что записывается Excel
-        ui::display_formatted_text("\nУспешно выполнено.", Some(&cyan));
+        ui::display_formatted_text(
+            tr("\nУспешно выполнено.", "\nCompleted successfully."),
+            Some(&cyan),
+        );
 
-        let base_msg = format!("Собрано {files_counter} файла(ов).");
-        let footer_msg = format!(r#"Создан файл "{}""#, report_path.display());
+        let base_msg = tr(
+            format!("Собрано {files_counter} файла(ов)."),
+            format!("Collected {files_counter} file(s)."),
+        );
+        let footer_msg = tr(
+            format!(r#"Создан файл "{}""#, report_path.display()),
+            format!(r#"Created file "{}""#, report_path.display()),
+        );
         let full_msg = format!("{base_msg}\n{footer_msg}\n");
 
         ui::display_formatted_text(&full_msg, None);
+
+        if !not_collected.is_empty() {
+            let mut not_collected_msg = tr(
+                format!(
+                    "Не собраны {} файла(ов) (причины в реестре замечаний отчета):",
+                    not_collected.len()
+                ),
+                format!(
+                    "Not collected {} file(s) (see the issues register of the report for the reasons):",
+                    not_collected.len()
+                ),
+            );
+            for file_path in &not_collected {
+                not_collected_msg = format!("{not_collected_msg}\n{}", file_path.display());
+            }
+            ui::display_formatted_text(&not_collected_msg, Some(&red));
+        }
         thread::sleep(Duration::from_secs(SUCCESS_PAUSE_DURATION));
         continue 'main_loop;
     }
@@ -184,7 +373,7 @@ fn main() {
 
 fn display_error_and_wait(err: Error<'_>) {
     let red = Style::new().red();
-    ui::display_formatted_text("\nВозникла ошибка.", Some(&red));
+    ui::display_formatted_text(tr("\nВозникла ошибка.", "\nAn error occurred."), Some(&red));
 
     let error_message = format!("\n{}", &err.to_string());
     ui::display_formatted_text(&error_message, None);
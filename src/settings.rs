@@ -0,0 +1,155 @@
+use crate::errors::Error;
+use crate::extract::DiscoveryOptions;
+use crate::load::OutputFormat;
+use crate::shared::lang::Lang;
+use crate::shared::utils;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SETTINGS_FILE_EXTENSION: &str = ".ini";
+const LANG_ENV_VAR: &str = "KS2_ETL_LANG";
+
+// Настройки программы. Читаются из необязательного файла "<имя программы>.ini" рядом с ".exe"
+// (строки вида "ключ = значение", комментарии начинаются с '#' или ';'),
+// переменные окружения имеют приоритет над файлом.
+#[derive(Debug, Default)]
+pub struct Settings {
+    pub lang: Lang,
+    pub totals_aliases: Vec<(String, String)>, // "totals_alias = синоним => каноническое имя", ключ повторяется
+    pub group_percent_totals: bool,
+    pub totals_order: Vec<String>, // "totals_order = имя итоговой строки", ключ повторяется в порядке столбцов отчета
+    pub output_format: OutputFormat,
+    pub relative_links: bool, // ссылки на акты относительно папки отчета
+    pub link_root: Option<PathBuf>, // ссылки на акты относительно этой папки (в ней будет лежать отчет)
+    pub link_maps: Vec<(String, String)>, // "link_map = начало пути => замена", ключ повторяется
+    pub discovery: DiscoveryOptions,
+}
+
+impl Settings {
+    pub fn load() -> Result<Settings, Error<'static>> {
+        let mut settings = Settings::default();
+        let file_path = utils::get_path_next_to_exe(SETTINGS_FILE_EXTENSION);
+
+        if file_path.is_file() {
+            let text = fs::read_to_string(&file_path).map_err(|err| Error::SettingsFileRead {
+                file_path: file_path.clone(),
+                err,
+            })?;
+            settings.apply_text(&text, &file_path)?;
+        }
+
+        if let Ok(value) = env::var(LANG_ENV_VAR) {
+            settings
+                .apply_entry("lang", &value)
+                .map_err(|_| Error::SettingsInvalidEntry {
+                    file_path: None,
+                    line_number: 0,
+                    entry: format!("{LANG_ENV_VAR}={value}"),
+                })?;
+        }
+
+        Ok(settings)
+    }
+
+    // Настройки из текста файла: ключи проверяются тестами модулей, которые эти ключи используют
+    #[cfg(test)]
+    pub fn from_text(text: &str) -> Result<Settings, Error<'static>> {
+        let mut settings = Settings::default();
+        settings.apply_text(text, Path::new(""))?;
+        Ok(settings)
+    }
+
+    fn apply_text(&mut self, text: &str, file_path: &Path) -> Result<(), Error<'static>> {
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            let invalid_entry = || Error::SettingsInvalidEntry {
+                file_path: Some(file_path.to_path_buf()),
+                line_number: index + 1,
+                entry: line.to_string(),
+            };
+
+            let (key, value) = line.split_once('=').ok_or_else(invalid_entry)?;
+            self.apply_entry(key.trim(), value.trim())
+                .map_err(|_| invalid_entry())?;
+        }
+        Ok(())
+    }
+
+    fn apply_entry(&mut self, key: &str, value: &str) -> Result<(), ()> {
+        match key.to_lowercase().as_str() {
+            "lang" => self.lang = Lang::parse(value).ok_or(())?,
+            "totals_alias" => {
+                let (alias, name) = value.split_once("=>").ok_or(())?;
+                let (alias, name) = (alias.trim(), name.trim());
+                if alias.is_empty() || name.is_empty() {
+                    return Err(());
+                }
+                self.totals_aliases
+                    .push((alias.to_string(), name.to_string()));
+            }
+            "group_percent_totals" => self.group_percent_totals = parse_bool(value).ok_or(())?,
+            "totals_order" if !value.is_empty() => self.totals_order.push(value.to_string()),
+            "output_format" => self.output_format = OutputFormat::parse(value).ok_or(())?,
+            "relative_links" => self.relative_links = parse_bool(value).ok_or(())?,
+            "link_root" if !value.is_empty() => self.link_root = Some(PathBuf::from(value)),
+            "include" | "exclude" if DiscoveryOptions::is_valid_glob(value) => {
+                let patterns = match key.to_lowercase().as_str() {
+                    "include" => &mut self.discovery.include,
+                    _ => &mut self.discovery.exclude,
+                };
+                patterns.push(value.to_string());
+            }
+            "exclude_marker" => self.discovery.exclusion_marker = value.to_string(),
+            "max_depth" => self.discovery.max_depth = Some(value.parse().map_err(|_| ())?),
+            "follow_symlinks" => self.discovery.follow_symlinks = parse_bool(value).ok_or(())?,
+            "sheet_name" => {
+                let (pattern, sheet_name) = value.split_once("=>").ok_or(())?;
+                let (pattern, sheet_name) = (pattern.trim(), sheet_name.trim());
+                if sheet_name.is_empty() || !DiscoveryOptions::is_valid_glob(pattern) {
+                    return Err(());
+                }
+                self.discovery
+                    .sheet_names
+                    .push((pattern.to_string(), sheet_name.to_string()));
+            }
+            "link_map" => {
+                let (from, to) = value.split_once("=>").ok_or(())?;
+                let (from, to) = (from.trim(), to.trim());
+                if from.is_empty() || to.is_empty() {
+                    return Err(());
+                }
+                self.link_maps.push((from.to_string(), to.to_string()));
+            }
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" | "да" => Some(true),
+        "false" | "no" | "off" | "0" | "нет" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Settings;
+    use crate::shared::lang::Lang;
+
+    #[test]
+    fn settings_file_syntax() {
+        let settings =
+            Settings::from_text("# комментарий\n\n; комментарий\n  lang =  en  \n").unwrap();
+        assert_eq!(settings.lang, Lang::En);
+        assert!(Settings::from_text("язык = en").is_err());
+        assert!(Settings::from_text("lang").is_err());
+    }
+}
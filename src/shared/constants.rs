@@ -1,6 +1,23 @@
 pub const XL_FILE_EXTENSION: &str = ".xlsm";
 
+// файл с правилами исключения из сбора (синтаксис .gitignore), действует в своей папке и вложенных
+pub const IGNORE_FILE_NAME: &str = ".ks2ignore";
+
+// файл с таким расширением, указанный вместо папки, - список файлов к сбору
+pub const MANIFEST_FILE_EXTENSIONS: [&str; 2] = [".txt", ".csv"];
+
+// окончание имени итоговой строки, в которую выносится процент надбавки ("... 4%" -> "..., %")
+pub const TOTALS_PERCENT_SUFFIX: &str = ", %";
+
 pub const CONSOLE_LEFT_MARGIN_IN_SPACES: usize = 2;
 // pub const CONSOLE_TOP_MARGIN_IN_ROW: usize = 1;
 
-pub const SUCCESS_PAUSE_DURATION: u64 = 1;
\ No newline at end of file
+pub const SUCCESS_PAUSE_DURATION: u64 = 1;
+
+// режим наблюдения: период опроса папки и время без изменений, после которого пересобирается отчет
+pub const WATCH_POLL_INTERVAL: u64 = 2;
+pub const WATCH_QUIET_PERIOD: u64 = 5;
+// пауза перед повтором неудачной пересборки растет до этого предела; после стольких неудач подряд
+// пользователю предлагается устранить причину
+pub const WATCH_MAX_RETRY_DELAY: u64 = 300;
+pub const WATCH_FAILED_REBUILDS_TO_REPORT: u32 = 3;
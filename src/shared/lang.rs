@@ -0,0 +1,59 @@
+use std::sync::OnceLock;
+
+// Язык сообщений программы и заголовков отчета
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Lang {
+    #[default]
+    Ru,
+    En,
+}
+
+impl Lang {
+    pub fn parse(text: &str) -> Option<Lang> {
+        match text.trim().to_lowercase().as_str() {
+            "ru" | "rus" | "russian" | "русский" => Some(Lang::Ru),
+            "en" | "eng" | "english" | "английский" => Some(Lang::En),
+            _ => None,
+        }
+    }
+}
+
+// Язык устанавливается один раз при запуске программы (из настроек или переменной окружения),
+// глобальное хранение нужно для Display у ошибок, куда язык иначе не передать
+static CURRENT_LANG: OnceLock<Lang> = OnceLock::new();
+
+pub fn set_current(lang: Lang) {
+    let _ = CURRENT_LANG.set(lang);
+}
+
+pub fn current() -> Lang {
+    CURRENT_LANG.get().copied().unwrap_or(Lang::Ru)
+}
+
+// Выбор варианта текста на текущем языке: первым аргументом всегда русский вариант
+pub fn tr<T>(ru: T, en: T) -> T {
+    match current() {
+        Lang::Ru => ru,
+        Lang::En => en,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lang;
+    use crate::settings::Settings;
+
+    #[test]
+    fn lang_parse() {
+        assert_eq!(Lang::parse(" EN "), Some(Lang::En));
+        assert_eq!(Lang::parse("русский"), Some(Lang::Ru));
+        assert_eq!(Lang::parse("de"), None);
+    }
+
+    #[test]
+    fn lang_setting() {
+        assert_eq!(Settings::from_text("lang = en").unwrap().lang, Lang::En);
+        assert_eq!(Settings::from_text("LANG = ru").unwrap().lang, Lang::Ru);
+        assert!(Settings::from_text("lang = de").is_err());
+    }
+}
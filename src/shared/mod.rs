@@ -1,3 +1,4 @@
 pub mod constants;
+pub mod lang;
 pub mod types;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
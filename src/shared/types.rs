@@ -2,4 +2,115 @@
 pub enum XlDataType {
     String(String),
     Float(f64),
-}
\ No newline at end of file
+    Int(i64),
+    Date(XlDate),
+    Bool(bool),
+    Error(String), // ошибка Excel в исходной ячейке ("#REF!", "#DIV/0!" и т.п.)
+}
+
+// Календарная дата без времени: в шапке акта время не используется
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XlDate {
+    pub year: i16,
+    pub month: i8,
+    pub day: i8,
+}
+
+impl XlDate {
+    pub fn new(year: i16, month: i8, day: i8) -> Option<XlDate> {
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(XlDate { year, month, day })
+    }
+
+    // Серийный номер даты Excel (система 1900 г.). Excel считает 1900 г. високосным,
+    // поэтому начиная с несуществующего 29.02.1900 номера смещены на единицу
+    pub fn from_excel_serial(serial: f64) -> Option<XlDate> {
+        // 9999-12-31 - последняя дата, которую допускает Excel
+        if !(1.0..2_958_466.0).contains(&serial) {
+            return None;
+        }
+        let mut days = serial.trunc() as i64;
+        if days >= 60 {
+            days -= 1;
+        }
+        // число дней от 1970-01-01 до 1899-12-31
+        let (year, month, day) = civil_from_days(days - 25_568);
+        XlDate::new(year as i16, month as i8, day as i8)
+    }
+
+    // Текстовая дата в виде "дд.мм.гггг" (так даты часто записаны в формах)
+    pub fn parse_dmy(text: &str) -> Option<XlDate> {
+        let mut parts = text.trim().split('.');
+        let day = parse_digits(parts.next()?, 2)?;
+        let month = parse_digits(parts.next()?, 2)?;
+        let year = parse_digits(parts.next()?, 4)?;
+        if parts.next().is_some() {
+            return None;
+        }
+        XlDate::new(year, month as i8, day as i8)
+    }
+
+    // Дата в формате ISO 8601 "гггг-мм-дд", допускается хвост со временем
+    pub fn parse_iso(text: &str) -> Option<XlDate> {
+        let date_part = text.trim().get(..10)?;
+        let mut parts = date_part.split('-');
+        let year = parse_digits(parts.next()?, 4)?;
+        let month = parse_digits(parts.next()?, 2)?;
+        let day = parse_digits(parts.next()?, 2)?;
+        XlDate::new(year, month as i8, day as i8)
+    }
+}
+
+fn parse_digits(text: &str, len: usize) -> Option<i16> {
+    if text.len() != len || !text.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+// Дата по числу дней от 1970-01-01 (алгоритм Г. Хиннанта для пролептического григорианского календаря)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XlDate;
+
+    #[test]
+    fn xl_date_test() {
+        assert_eq!(XlDate::from_excel_serial(1.0), XlDate::new(1900, 1, 1));
+        assert_eq!(XlDate::from_excel_serial(61.0), XlDate::new(1900, 3, 1));
+        assert_eq!(XlDate::from_excel_serial(44408.0), XlDate::new(2021, 7, 31));
+        assert_eq!(
+            XlDate::from_excel_serial(43731.75),
+            XlDate::new(2019, 9, 23)
+        );
+        assert_eq!(XlDate::from_excel_serial(0.0), None);
+
+        assert_eq!(XlDate::parse_dmy(" 01.07.2021 "), XlDate::new(2021, 7, 1));
+        assert_eq!(XlDate::parse_dmy("31.02.2021"), None);
+        assert_eq!(XlDate::parse_dmy("1.07.2021"), None);
+        assert_eq!(
+            XlDate::parse_iso("2021-07-31T00:00:00"),
+            XlDate::new(2021, 7, 31)
+        );
+    }
+}
@@ -1,3 +1,6 @@
+use std::env;
+use std::path::PathBuf;
+
 pub fn get_xl_column_letter(zero_based_column: u16) -> String {
     let integer = zero_based_column / 26;
     let remainder = (zero_based_column % 26) as u8;
@@ -10,6 +13,17 @@ pub fn get_xl_column_letter(zero_based_column: u16) -> String {
     get_xl_column_letter(integer - 1) + &ch
 }
 
+// Путь к файлу рядом с исполняемым файлом программы: имя то же, что у ".exe", расширение заданное
+pub fn get_path_next_to_exe(extension: &str) -> PathBuf {
+    let string_path = env::args()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(".exe")
+        .to_owned()
+        + extension;
+
+    PathBuf::from(string_path)
+}
 
 #[cfg(test)]
 mod tests {
@@ -25,4 +39,4 @@ mod tests {
         let result = get_xl_column_letter(1465);
         assert_eq!(result, "BDJ".to_string());
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
-use crate::shared::constants::CONSOLE_LEFT_MARGIN_IN_SPACES;
 use crate::errors::Error;
+use crate::extract::ExtractedBooks;
+use crate::shared::constants::CONSOLE_LEFT_MARGIN_IN_SPACES;
+use crate::shared::lang::tr;
 use console::{Style, Term};
 use dialoguer::Input;
 use std::io;
@@ -7,7 +9,15 @@ use std::path::PathBuf;
 use std::thread; // для засыпания на секунду-две
 use std::time::Duration; // для засыпания на секунду-две // для очистки консоли перед выводом полезных сообщений
 
-pub fn user_input() -> Result<(PathBuf, String), Error<'static>> {
+// Что пользователь попросил сделать: собрать данные по пути, разобрать один файл для диагностики
+// или наблюдать за папкой (списком файлов), обновляя отчет при изменении актов
+pub enum UserRequest {
+    Collect(PathBuf, String),
+    Explain(PathBuf, String),
+    Watch(PathBuf, String),
+}
+
+pub fn user_input() -> Result<UserRequest, Error<'static>> {
     loop {
         println!("\n");
         let entered_text = inputting_path();
@@ -17,7 +27,7 @@ pub fn user_input() -> Result<(PathBuf, String), Error<'static>> {
         // при том что .exists() чувствителен к этой разнице. Длинное тире очень часто встречается так как windows генерирует его
         // автоматически к любому дубликату в файловой системы (в виде постфикса "— копия" перед расширением файла)
         if path.exists() {
-            break Ok((path, entered_sheet_name()));
+            break Ok(UserRequest::Collect(path, entered_sheet_name()));
         } else if path.to_string_lossy().contains("- копия") {
             break Err(Error::InvalidDashInUserPath {
                 entered_path: path.clone(),
@@ -38,6 +48,64 @@ pub fn user_input() -> Result<(PathBuf, String), Error<'static>> {
                 thread::sleep(Duration::from_secs(2));
                 continue;
             }
+            // "explain", в том числе набранное в русской раскладке
+            ch if ch == "explain" || ch == "учздфшт" => {
+                display_formatted_text(
+                    tr(
+                        "\nРазбор одного файла: будет показано, где найдены ключевые слова и значения.",
+                        "\nSingle file analysis: shows where the keywords and values were found.",
+                    ),
+                    None,
+                );
+                let path = PathBuf::from(inputting_path());
+
+                if path.is_file() {
+                    break Ok(UserRequest::Explain(path, entered_sheet_name()));
+                } else if path.to_string_lossy().contains("- копия") {
+                    break Err(Error::InvalidDashInUserPath {
+                        entered_path: path.clone(),
+                    });
+                }
+
+                display_formatted_text(
+                    tr(
+                        "Для разбора требуется путь к существующему файлу.",
+                        "The analysis requires a path to an existing file.",
+                    ),
+                    None,
+                );
+                thread::sleep(Duration::from_secs(2));
+                continue;
+            }
+            // "watch", в том числе набранное в русской раскладке
+            ch if ch == "watch" || ch == "цфеср" => {
+                display_formatted_text(
+                    tr(
+                        "\nНаблюдение за папкой или списком файлов к сбору: отчет будет пересобираться при появлении и изменении актов.",
+                        "\nWatching a folder or a list of files to collect: the report is rebuilt when acts are added or changed.",
+                    ),
+                    None,
+                );
+                let path = PathBuf::from(inputting_path());
+
+                if path.is_dir() || ExtractedBooks::is_manifest(&path) {
+                    break Ok(UserRequest::Watch(path, entered_sheet_name()));
+                } else if path.to_string_lossy().contains("- копия") {
+                    break Err(Error::InvalidDashInUserPath {
+                        entered_path: path.clone(),
+                    });
+                }
+
+                display_formatted_text(
+                    tr(
+                        "Для наблюдения требуется путь к существующей папке или списку файлов к сбору («.txt» или «.csv»).",
+                        "Watching requires a path to an existing folder or list of files to collect («.txt» or «.csv»).",
+                    ),
+                    None,
+                );
+                thread::sleep(Duration::from_secs(2));
+                continue;
+            }
             _ => continue,
         }
     }
@@ -45,7 +113,10 @@ pub fn user_input() -> Result<(PathBuf, String), Error<'static>> {
 
 pub fn display_first_lines(is_visible: bool) {
     let optional_text = if is_visible {
-        "       Для получения подробной информации о программе введите \"info\" вместо пути."
+        tr(
+            "       Для получения подробной информации о программе введите \"info\" вместо пути.",
+            "       For detailed information about the program, enter \"info\" instead of a path.",
+        )
     } else {
         ""
     };
@@ -55,16 +126,34 @@ pub fn display_first_lines(is_visible: bool) {
 }
 
 pub fn display_help() {
-    let msg = r#"------------------------------------------------------------------------------------------------------------
+    let msg = tr(
+        r#"------------------------------------------------------------------------------------------------------------
 
 ● Используйте CTRL + V, чтобы вставить скопированный путь к папке или файлу с данными, которые вы хотите собрать.
 ● Программа будет собирать данные из файлов Excel по указанному пути, включая вложенные папки.
 ● Собираются только файлы с расширением «.xlsm».
+● Вместо папки можно указать список файлов к сбору («.txt» или «.csv»): путь к файлу в строке, через «;» - имя листа, если оно отличается от введенного.
+● Введите "explain" вместо пути, чтобы разобрать один файл и увидеть, где программа нашла ключевые слова и значения.
+● Введите "watch" вместо пути, чтобы наблюдать за папкой или списком файлов: отчет будет обновляться при появлении и изменении актов.
 ● Полезный совет:
     - переименуйте файл Excel, добавив символ "@", и программа не будет собирать его данные;
     - переименуйте папку, добавив символ "@", и программа проигнорирует ее содержимое.
 
-------------------------------------------------------------------------------------------------------------"#;
+------------------------------------------------------------------------------------------------------------"#,
+        r#"------------------------------------------------------------------------------------------------------------
+
+● Use CTRL + V to paste the copied path to the folder or file with the data you want to collect.
+● The program will collect data from Excel files at the specified path, including subfolders.
+● Only files with the «.xlsm» extension are collected.
+● Instead of a folder, you can specify a list of files to collect («.txt» or «.csv»): a file path per line, followed by «;» and a sheet name if it differs from the entered one.
+● Enter "explain" instead of a path to analyse a single file and see where the program found the keywords and values.
+● Enter "watch" instead of a path to watch a folder or a list of files: the report is updated when acts are added or changed.
+● Helpful hints:
+    - rename an Excel file by adding the "@" symbol, and the program will not collect its data;
+    - rename a folder by adding the "@" symbol, and the program will ignore its contents.
+
+------------------------------------------------------------------------------------------------------------"#,
+    );
 
     display_formatted_text(msg, None);
 }
@@ -84,8 +173,9 @@ fn display_info() {
     display_first_lines(false);
     display_help();
 
-    let msg = format!(
-        r#"
+    let msg = tr(
+        format!(
+            r#"
             Наименование продукта:        «Сборщик данных из актов формы "КС-2"»
             Версия продукта:              {}
             Дата основания проекта:       02.06.2022
@@ -97,17 +187,33 @@ fn display_info() {
             Cметно-договорное управление.
 
 ------------------------------------------------------------------------------------------------------------"#,
-        env!("CARGO_PKG_VERSION")
+            env!("CARGO_PKG_VERSION")
+        ),
+        format!(
+            r#"
+            Product name:                 «Data collector from acts of the "KS-2" form»
+            Product version:              {}
+            Project start date:           02.06.2022
+            GitHub.com address:           https://github.com/Soskretkov/ks2_etl
+            Author:                       Sergey Oskretkov
+
+            Made for: LLC «Trest Rosspecenergomontazh»,
+            Altufyevskoye highway, 43, bldg. 1, Moscow, 127410,
+            Estimating and Contract Department.
+
+------------------------------------------------------------------------------------------------------------"#,
+            env!("CARGO_PKG_VERSION")
+        ),
     );
     display_formatted_text(&msg, None);
 }
 
 fn inputting_path() -> String {
-    display_formatted_text("Введите путь:", None);
+    display_formatted_text(tr("Введите путь:", "Enter the path:"), None);
     let mut text = String::new();
     io::stdin()
         .read_line(&mut text)
-        .expect("Ошибка чтения ввода");
+        .unwrap_or_else(|_| panic!("{}", tr("Ошибка чтения ввода", "Input read error")));
 
     text = text.trim().to_string();
     text
@@ -115,17 +221,21 @@ fn inputting_path() -> String {
 
 fn entered_sheet_name() -> String {
     let _ = Term::stdout().clear_screen();
-    let msg = prepend_spaces_to_non_empty_lines(
+    let msg = prepend_spaces_to_non_empty_lines(tr(
         "Подтвердите лист или укажите другой.
 Не имеет значения, используете ли вы прописные или строчные буквы при указании листа.
 
 Имя листа",
-    );
+        "Confirm the sheet or specify another one.
+It does not matter whether you use upper or lower case letters when specifying the sheet.
+
+Sheet name",
+    ));
     let entered_sh_name: String = Input::new()
         .with_prompt(msg)
         .with_initial_text("Лист1")
         .interact()
-        .expect("Ошибка чтения ввода");
+        .unwrap_or_else(|_| panic!("{}", tr("Ошибка чтения ввода", "Input read error")));
 
     let _ = Term::stdout().clear_screen();
     entered_sh_name
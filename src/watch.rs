@@ -0,0 +1,471 @@
+use crate::errors::Error;
+use crate::extract::{
+    self, Act, ActStore, Book, ExtractedBooks, PackedAct, Sheet, SkippedEntry, TotalsNameDictionary,
+};
+use crate::load::{Report, ReportLinks, TotalsStats};
+use crate::settings::Settings;
+use crate::shared::constants::{
+    WATCH_FAILED_REBUILDS_TO_REPORT, WATCH_MAX_RETRY_DELAY, WATCH_POLL_INTERVAL, WATCH_QUIET_PERIOD,
+};
+use crate::shared::lang::tr;
+use crate::shared::utils;
+use crate::ui;
+use console::Style;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+// Режим наблюдения: папка (или список файлов к сбору) опрашивается раз в несколько секунд, отчет пересобирается, когда файлы
+// перестают меняться (копирование пачки актов дает серию изменений, а не одно).
+// Опрос, а не уведомления файловой системы: в общих сетевых папках уведомления ненадежны.
+// Разобранные акты хранятся между пересборками, заново читаются только новые и измененные файлы
+pub fn watch(path: &PathBuf, sh_name: &str, settings: &Settings) -> Result<(), Error<'static>> {
+    let report_path = utils::get_path_next_to_exe(settings.output_format.extension());
+
+    // список файлов к сбору перечитывается при каждом опросе: правка списка тоже обновляет отчет
+    let is_manifest = ExtractedBooks::is_manifest(path);
+    let scan = || {
+        if is_manifest {
+            ExtractedBooks::scan_manifest(path, &settings.discovery)
+        } else {
+            ExtractedBooks::scan(path, &settings.discovery)
+        }
+    };
+    // ошибки настроек отбора выявляются до запуска наблюдения
+    let mut books = scan()?;
+
+    ui::display_formatted_text(
+        &tr(
+            format!(
+                "\nНаблюдение за \"{}\": отчет будет обновляться при изменении актов.\nНажмите Enter, чтобы остановить наблюдение.",
+                path.display()
+            ),
+            format!(
+                "\nWatching \"{}\": the report is updated when acts change.\nPress Enter to stop watching.",
+                path.display()
+            ),
+        ),
+        None,
+    );
+
+    // поток ждет Enter; закрытый ввод тоже останавливает наблюдение
+    let (stop_tx, stop_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = io::stdin().read_line(&mut String::new());
+        let _ = stop_tx.send(());
+    });
+
+    let mut parsed_files: HashMap<PathBuf, ParsedFile> = HashMap::new();
+    let mut last_snapshot = snapshot(&books);
+    let mut schedule = RebuildSchedule::new(Instant::now());
+    let red = Style::new().red();
+
+    loop {
+        if schedule.is_due(Instant::now()) {
+            match rebuild(
+                &books,
+                &last_snapshot,
+                &mut parsed_files,
+                sh_name,
+                settings,
+                &report_path,
+            ) {
+                Ok(summary) => {
+                    schedule.succeeded();
+                    ui::display_formatted_text(&summary.status_line(), None);
+                }
+                // например, отчет открыт в Excel и не может быть перезаписан: попытка повторится позже
+                Err(err) => {
+                    let failed_rebuilds = schedule.failed(Instant::now());
+                    let retry_in = retry_delay(failed_rebuilds).as_secs();
+                    let msg = tr(
+                        format!("\nОтчет не обновлен, повторная попытка через {retry_in} с.\n{err}"),
+                        format!("\nThe report was not updated, the next attempt is in {retry_in} s.\n{err}"),
+                    );
+                    ui::display_formatted_text(&msg, Some(&red));
+                    if failed_rebuilds == WATCH_FAILED_REBUILDS_TO_REPORT {
+                        let msg = tr(
+                            format!(
+                                "\nОтчет не удается обновить {failed_rebuilds} раза подряд: закройте файл отчета, если он открыт в Excel, или устраните причину ошибки. Попытки будут продолжаться все реже (не реже раза в {WATCH_MAX_RETRY_DELAY} с), изменение актов вызовет попытку сразу после паузы. Нажмите Enter, чтобы остановить наблюдение."
+                            ),
+                            format!(
+                                "\nThe report failed to update {failed_rebuilds} times in a row: close the report file if it is open in Excel or fix the cause of the error. Attempts will continue less often (at least once every {WATCH_MAX_RETRY_DELAY} s), a change of acts triggers an attempt right after the pause. Press Enter to stop watching."
+                            ),
+                        );
+                        ui::display_formatted_text(&msg, Some(&red));
+                    }
+                }
+            }
+        }
+
+        match stop_rx.recv_timeout(Duration::from_secs(WATCH_POLL_INTERVAL)) {
+            Err(RecvTimeoutError::Timeout) => (),
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        // поток ввода все еще ждет Enter, поэтому наблюдение не прерывается ошибкой, а продолжается
+        books = match scan() {
+            Ok(books) => books,
+            Err(err) => {
+                let msg = tr(
+                    format!("\nНе удалось просмотреть папку, повтор при следующем опросе.\n{err}"),
+                    format!(
+                        "\nFailed to scan the folder, it will be retried on the next check.\n{err}"
+                    ),
+                );
+                ui::display_formatted_text(&msg, Some(&red));
+                continue;
+            }
+        };
+        let current_snapshot = snapshot(&books);
+        // каждое новое изменение откладывает пересборку
+        if current_snapshot != last_snapshot {
+            last_snapshot = current_snapshot;
+            schedule.files_changed(Instant::now());
+        }
+    }
+
+    ui::display_formatted_text(tr("Наблюдение остановлено.", "Watching stopped."), None);
+    Ok(())
+}
+
+// Когда пересобирать отчет: сразу после запуска, затем по истечении периода без изменений.
+// После неудачной пересборки пауза перед повтором растет вдвое с каждой неудачей подряд
+struct RebuildSchedule {
+    due_at: Option<Instant>,
+    failed_rebuilds: u32,
+}
+
+impl RebuildSchedule {
+    fn new(now: Instant) -> RebuildSchedule {
+        RebuildSchedule {
+            due_at: Some(now),
+            failed_rebuilds: 0,
+        }
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.due_at.is_some_and(|due_at| now >= due_at)
+    }
+
+    fn files_changed(&mut self, now: Instant) {
+        self.due_at = Some(now + Duration::from_secs(WATCH_QUIET_PERIOD));
+    }
+
+    fn succeeded(&mut self) {
+        self.due_at = None;
+        self.failed_rebuilds = 0;
+    }
+
+    // Возвращает количество неудач подряд
+    fn failed(&mut self, now: Instant) -> u32 {
+        self.failed_rebuilds += 1;
+        self.due_at = Some(now + retry_delay(self.failed_rebuilds));
+        self.failed_rebuilds
+    }
+}
+
+fn retry_delay(failed_rebuilds: u32) -> Duration {
+    let factor = 2_u64.saturating_pow(failed_rebuilds.saturating_sub(1));
+    Duration::from_secs(
+        WATCH_QUIET_PERIOD
+            .saturating_mul(factor)
+            .min(WATCH_MAX_RETRY_DELAY),
+    )
+}
+
+// Отпечаток файла: по нему видно, что файл изменился и акт нужно разобрать заново
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+fn file_stamp(file_path: &Path) -> Option<FileStamp> {
+    fs::metadata(file_path).ok().map(|metadata| FileStamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+// Файл, за которым ведется наблюдение: смена листа в списке к сбору тоже требует разобрать акт заново
+#[derive(Debug, Clone, PartialEq)]
+struct WatchedFile {
+    path: PathBuf,
+    stamp: Option<FileStamp>,
+    sheet_name: Option<String>, // лист, заданный для файла списком к сбору или шаблоном из настроек
+}
+
+fn snapshot(books: &ExtractedBooks) -> Vec<WatchedFile> {
+    books
+        .file_paths
+        .iter()
+        .map(|file_path| WatchedFile {
+            path: file_path.clone(),
+            stamp: file_stamp(file_path),
+            sheet_name: books.sheet_names.get(file_path).cloned(),
+        })
+        .collect()
+}
+
+// Разобранный файл: акт или текст ошибки, из-за которой он не попал в отчет
+struct ParsedFile {
+    stamp: Option<FileStamp>,
+    sheet_name: Option<String>,
+    act: Result<PackedAct, String>,
+}
+
+// Разобранные файлы приводятся к отпечатку: удаленные забываются, новые и измененные разбираются заново.
+// Возвращает количество разобранных файлов
+fn refresh_parsed_files(
+    parsed_files: &mut HashMap<PathBuf, ParsedFile>,
+    snapshot: &[WatchedFile],
+    mut parse: impl FnMut(&WatchedFile) -> Result<PackedAct, String>,
+) -> usize {
+    parsed_files.retain(|file_path, _| snapshot.iter().any(|file| &file.path == file_path));
+    let mut reparsed = 0;
+    for file in snapshot {
+        if parsed_files.get(&file.path).is_some_and(|parsed| {
+            parsed.stamp == file.stamp && parsed.sheet_name == file.sheet_name
+        }) {
+            continue;
+        }
+        reparsed += 1;
+        let parsed = ParsedFile {
+            stamp: file.stamp,
+            sheet_name: file.sheet_name.clone(),
+            act: parse(file),
+        };
+        parsed_files.insert(file.path.clone(), parsed);
+    }
+    reparsed
+}
+
+struct RebuildSummary {
+    collected: usize,
+    reparsed: usize,
+    not_collected: Vec<PathBuf>,
+}
+
+impl RebuildSummary {
+    fn status_line(&self) -> String {
+        let mut msg = tr(
+            format!(
+                "\nОтчет обновлен: в отчете {} акт(ов), заново разобрано файлов: {}.",
+                self.collected, self.reparsed
+            ),
+            format!(
+                "\nThe report was updated: {} act(s) in the report, files parsed again: {}.",
+                self.collected, self.reparsed
+            ),
+        );
+        if !self.not_collected.is_empty() {
+            let header = tr(
+                "Не собраны (причины в реестре замечаний):",
+                "Not collected (see the issues register for the reasons):",
+            );
+            msg = format!("{msg}\n{header}");
+            for file_path in &self.not_collected {
+                msg = format!("{msg}\n{}", file_path.display());
+            }
+        }
+        msg
+    }
+}
+
+fn rebuild(
+    books: &ExtractedBooks,
+    snapshot: &[WatchedFile],
+    parsed_files: &mut HashMap<PathBuf, ParsedFile>,
+    sh_name: &str,
+    settings: &Settings,
+    report_path: &Path,
+) -> Result<RebuildSummary, Error<'static>> {
+    let reparsed = refresh_parsed_files(parsed_files, snapshot, |file| {
+        let sh_name = file.sheet_name.as_deref().unwrap_or(sh_name);
+        parse_act(file.path.clone(), sh_name)
+    });
+
+    // имена итоговых строк сводятся заново: добавленный акт может изменить состав столбцов
+    let mut totals_names =
+        TotalsNameDictionary::new(&settings.totals_aliases, settings.group_percent_totals);
+    let totals_order = extract::totals_column_order(&settings.totals_order, &mut totals_names);
+    let mut act_store = ActStore::new()?;
+    let mut totals_stats = TotalsStats::default();
+    let mut skipped = books.skipped.clone();
+    let mut not_collected = Vec::new();
+    let mut collected = 0;
+
+    for WatchedFile {
+        path: file_path, ..
+    } in snapshot
+    {
+        match parsed_files.get(file_path).map(|parsed| &parsed.act) {
+            Some(Ok(packed_act)) => {
+                let mut act = packed_act.unpack()?;
+                extract::normalize_totals_names(&mut act, &mut totals_names);
+                totals_stats.add_act(&act);
+                act_store.push(&act)?;
+                collected += 1;
+            }
+            Some(Err(reason)) => {
+                skipped.push(SkippedEntry {
+                    path: file_path.clone(),
+                    reason: reason.clone(),
+                });
+                not_collected.push(file_path.clone());
+            }
+            None => (),
+        }
+    }
+    not_collected.extend(books.skipped.iter().map(|entry| entry.path.clone()));
+
+    let mut report = Report::new(
+        settings.output_format.create(report_path),
+        totals_stats,
+        &totals_order,
+        ReportLinks::new(
+            report_path,
+            settings.relative_links,
+            settings.link_root.as_deref(),
+            &settings.link_maps,
+        ),
+    )?;
+    for act in act_store.into_acts()? {
+        report = report.write(&act?)?;
+    }
+    report.write_skipped(&skipped)?;
+    report.write_and_close_report()?;
+
+    Ok(RebuildSummary {
+        collected,
+        reparsed,
+        not_collected,
+    })
+}
+
+// Ошибка акта не останавливает наблюдение: файл попадает в реестр замечаний и ждет исправления
+fn parse_act(file_path: PathBuf, sh_name: &str) -> Result<PackedAct, String> {
+    let book = Book::new(file_path).map_err(|err| err.to_string())?;
+    let sheet = Sheet::new(book, sh_name).map_err(|err| err.to_string())?;
+    let act = Act::new(sheet).map_err(|err| err.to_string())?;
+    Ok(PackedAct::new(&act))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        refresh_parsed_files, snapshot, FileStamp, ParsedFile, RebuildSchedule, WatchedFile,
+    };
+    use crate::extract::ExtractedBooks;
+    use crate::shared::constants::{WATCH_MAX_RETRY_DELAY, WATCH_QUIET_PERIOD};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant, SystemTime};
+
+    #[test]
+    fn snapshot_follows_file_changes() {
+        let dir = std::env::temp_dir().join(format!("ks2_etl_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("акт.xlsm");
+        std::fs::write(&file_path, "1").unwrap();
+        let books = ExtractedBooks {
+            file_paths: vec![file_path.clone()],
+            file_count_excluded: 0,
+            file_count_filtered: 0,
+            file_count_ignored: 0,
+            skipped: Vec::new(),
+            sheet_names: HashMap::new(),
+        };
+
+        let before = snapshot(&books);
+        assert_eq!(before, snapshot(&books));
+        std::fs::write(&file_path, "12").unwrap();
+        let changed = snapshot(&books);
+        std::fs::remove_file(&file_path).unwrap();
+        let removed = snapshot(&books);
+        let _ = std::fs::remove_dir(&dir);
+
+        assert_ne!(before, changed);
+        assert_eq!(changed[0].stamp.map(|stamp| stamp.len), Some(2));
+        assert_eq!(removed[0].stamp, None);
+    }
+
+    #[test]
+    fn rebuild_schedule_waits_for_quiet_period_and_backs_off() {
+        let quiet = Duration::from_secs(WATCH_QUIET_PERIOD);
+        let start = Instant::now();
+        let mut schedule = RebuildSchedule::new(start);
+        assert!(schedule.is_due(start));
+
+        schedule.succeeded();
+        assert!(!schedule.is_due(start + quiet * 100));
+
+        // каждое изменение откладывает пересборку на период тишины
+        schedule.files_changed(start);
+        schedule.files_changed(start + quiet / 2);
+        assert!(!schedule.is_due(start + quiet));
+        assert!(schedule.is_due(start + quiet / 2 + quiet));
+
+        // пауза перед повтором удваивается и ограничена сверху
+        let mut now = start;
+        for (failed_rebuilds, delay) in [(1, quiet), (2, quiet * 2), (3, quiet * 4)] {
+            assert_eq!(schedule.failed(now), failed_rebuilds);
+            assert!(!schedule.is_due(now + delay - Duration::from_millis(1)));
+            assert!(schedule.is_due(now + delay));
+            now += delay;
+        }
+        for _ in 0..20 {
+            schedule.failed(now);
+        }
+        let max_delay = Duration::from_secs(WATCH_MAX_RETRY_DELAY);
+        assert!(!schedule.is_due(now + max_delay - Duration::from_millis(1)));
+        assert!(schedule.is_due(now + max_delay));
+
+        // удачная пересборка сбрасывает счет неудач
+        schedule.succeeded();
+        assert_eq!(schedule.failed(now), 1);
+    }
+
+    #[test]
+    fn parsed_files_are_invalidated_by_stamp_and_sheet() {
+        let watched = |name: &str, len: u64, sheet_name: Option<&str>| WatchedFile {
+            path: PathBuf::from(name),
+            stamp: Some(FileStamp {
+                modified: Some(SystemTime::UNIX_EPOCH),
+                len,
+            }),
+            sheet_name: sheet_name.map(str::to_string),
+        };
+        let mut parsed_files: HashMap<PathBuf, ParsedFile> = HashMap::new();
+        let mut refresh = |snapshot: &[WatchedFile]| {
+            let mut parsed = Vec::new();
+            let count = refresh_parsed_files(&mut parsed_files, snapshot, |file| {
+                parsed.push(file.path.clone());
+                Err(String::new())
+            });
+            assert_eq!(count, parsed.len());
+            (parsed, parsed_files.len())
+        };
+
+        let a = watched("a.xlsm", 1, None);
+        let b = watched("b.xlsm", 1, None);
+        assert_eq!(refresh(&[a.clone(), b.clone()]).0.len(), 2);
+        assert_eq!(refresh(&[a.clone(), b.clone()]).0.len(), 0);
+
+        let b_changed = watched("b.xlsm", 2, None);
+        let (parsed, _) = refresh(&[a.clone(), b_changed.clone()]);
+        assert_eq!(parsed, vec![PathBuf::from("b.xlsm")]);
+
+        let a_other_sheet = watched("a.xlsm", 1, Some("КС-2"));
+        let (parsed, _) = refresh(&[a_other_sheet.clone(), b_changed]);
+        assert_eq!(parsed, vec![PathBuf::from("a.xlsm")]);
+
+        // удаленный файл забывается без разбора
+        assert_eq!(refresh(&[a_other_sheet]), (Vec::new(), 1));
+    }
+}